@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS users (
+    id CHAR(36) NOT NULL,
+    email VARCHAR(255) NOT NULL,
+    created_at DATETIME NOT NULL,
+    updated_at DATETIME NOT NULL,
+    PRIMARY KEY (id),
+    UNIQUE KEY users_email (email)
+);
@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS user_auth (
+    user_id CHAR(36) NOT NULL,
+    password_hash VARCHAR(255) NOT NULL,
+    created_at DATETIME NOT NULL,
+    updated_at DATETIME NOT NULL,
+    PRIMARY KEY (user_id),
+    CONSTRAINT user_auth_user_id FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+);
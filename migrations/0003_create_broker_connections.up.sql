@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS broker_connections (
+    user_id CHAR(36) NOT NULL,
+    broker VARCHAR(32) NOT NULL,
+    access_token TEXT NOT NULL,
+    refresh_token TEXT NOT NULL,
+    access_token_expires_at DATETIME NOT NULL,
+    refresh_token_expires_at DATETIME NOT NULL,
+    created_at DATETIME NOT NULL,
+    updated_at DATETIME NOT NULL,
+    PRIMARY KEY (user_id, broker),
+    CONSTRAINT broker_connections_user_id FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+);
@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS candles (
+    symbol VARCHAR(32) NOT NULL,
+    frequency_type VARCHAR(16) NOT NULL,
+    frequency INT UNSIGNED NOT NULL,
+    extended_hours BOOLEAN NOT NULL,
+    datetime BIGINT NOT NULL,
+    open DOUBLE NOT NULL,
+    high DOUBLE NOT NULL,
+    low DOUBLE NOT NULL,
+    close DOUBLE NOT NULL,
+    volume BIGINT NOT NULL,
+    PRIMARY KEY (symbol, frequency_type, frequency, extended_hours, datetime)
+);
@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS candle_ranges (
+    id BIGINT UNSIGNED NOT NULL AUTO_INCREMENT,
+    symbol VARCHAR(32) NOT NULL,
+    frequency_type VARCHAR(16) NOT NULL,
+    frequency INT UNSIGNED NOT NULL,
+    extended_hours BOOLEAN NOT NULL,
+    range_start BIGINT NOT NULL,
+    range_end BIGINT NOT NULL,
+    created_at DATETIME NOT NULL,
+    PRIMARY KEY (id),
+    KEY candle_ranges_series (symbol, frequency_type, frequency, extended_hours)
+);
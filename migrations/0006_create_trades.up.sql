@@ -0,0 +1,25 @@
+CREATE TABLE IF NOT EXISTS trades (
+    id CHAR(36) NOT NULL,
+    user_id CHAR(36) NOT NULL,
+    account_id BIGINT NOT NULL,
+    order_id BIGINT NOT NULL,
+    leg_id BIGINT NOT NULL,
+    symbol VARCHAR(64) NOT NULL,
+    asset_type VARCHAR(32) NOT NULL,
+    instruction VARCHAR(32) NOT NULL,
+    position_effect VARCHAR(32) NULL,
+    quantity DOUBLE NOT NULL,
+    price DOUBLE NOT NULL,
+    first_filled_at DATETIME NOT NULL,
+    last_filled_at DATETIME NOT NULL,
+    notes TEXT NOT NULL,
+    tags JSON NOT NULL,
+    setup VARCHAR(255) NULL,
+    screenshots JSON NOT NULL,
+    created_at DATETIME NOT NULL,
+    updated_at DATETIME NOT NULL,
+    PRIMARY KEY (id),
+    UNIQUE KEY trades_user_id_order_id_leg_id (user_id, order_id, leg_id),
+    KEY trades_user_id_first_filled_at (user_id, first_filled_at),
+    CONSTRAINT trades_user_id FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+);
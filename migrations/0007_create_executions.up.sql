@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS executions (
+    trade_id CHAR(36) NOT NULL,
+    sequence INT UNSIGNED NOT NULL,
+    user_id CHAR(36) NOT NULL,
+    activity_id BIGINT NULL,
+    quantity DOUBLE NOT NULL,
+    price DOUBLE NOT NULL,
+    filled_at DATETIME NOT NULL,
+    PRIMARY KEY (trade_id, sequence),
+    CONSTRAINT executions_trade_id FOREIGN KEY (trade_id) REFERENCES trades (id) ON DELETE CASCADE,
+    CONSTRAINT executions_user_id FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+);
@@ -0,0 +1,73 @@
+use crate::utils::crypto::CryptoError;
+use std::fmt::{self, Display, Formatter};
+use tokio::task::JoinError;
+
+const ER_DUP_ENTRY: u16 = 1062;
+
+#[derive(Debug)]
+pub enum DatabaseError {
+    /// A row with the same unique key already exists.
+    Conflict(String),
+    /// No pooled connection became available within the acquire timeout.
+    PoolTimeout,
+    /// A row the query had just written could not be read back.
+    MissingRow(String),
+    /// A stored secret could not be decrypted.
+    Crypto(CryptoError),
+    /// The blocking task running the query panicked or was cancelled.
+    Task(JoinError),
+    MySql(mysql::Error),
+}
+
+impl Display for DatabaseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            DatabaseError::Conflict(message) => write!(f, "conflict: {}", message),
+            DatabaseError::PoolTimeout => write!(f, "timed out waiting for a database connection"),
+            DatabaseError::MissingRow(message) => write!(f, "missing row: {}", message),
+            DatabaseError::Crypto(e) => write!(f, "crypto error: {}", e),
+            DatabaseError::Task(e) => write!(f, "database task failed: {}", e),
+            DatabaseError::MySql(e) => write!(f, "mysql error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for DatabaseError {}
+
+impl From<mysql::Error> for DatabaseError {
+    fn from(e: mysql::Error) -> Self {
+        match e {
+            mysql::Error::MySqlError(ref error) if error.code == ER_DUP_ENTRY => DatabaseError::Conflict(error.message.clone()),
+            mysql::Error::DriverError(mysql::DriverError::Timeout) => DatabaseError::PoolTimeout,
+            e => DatabaseError::MySql(e),
+        }
+    }
+}
+
+impl From<CryptoError> for DatabaseError {
+    fn from(e: CryptoError) -> Self {
+        DatabaseError::Crypto(e)
+    }
+}
+
+impl From<JoinError> for DatabaseError {
+    fn from(e: JoinError) -> Self {
+        DatabaseError::Task(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DatabaseError;
+
+    #[test]
+    fn test_from_mysql_error() {
+        let duplicate = mysql::Error::MySqlError(mysql::MySqlError {
+            state: "23000".to_string(),
+            message: "Duplicate entry 'trader@example.com' for key 'users_email'".to_string(),
+            code: 1062,
+        });
+        assert!(matches!(DatabaseError::from(duplicate), DatabaseError::Conflict(_)));
+        assert!(matches!(DatabaseError::from(mysql::Error::DriverError(mysql::DriverError::Timeout)), DatabaseError::PoolTimeout));
+    }
+}
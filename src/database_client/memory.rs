@@ -0,0 +1,210 @@
+use super::{
+    error::DatabaseError,
+    models::{BrokerConnection, Trade, User},
+    repository::{BrokerConnectionRepository, CandleRepository, TradeRepository, UserRepository},
+    CandleSeries, CreateUser, CreateUserAuth, GetUserAuthByEmail, TradeAnnotation, TradeFilter, UpsertBrokerConnection, UpsertTrade,
+};
+use crate::tda_client::price_history::Candle;
+use async_trait::async_trait;
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Mutex,
+};
+use uuid::Uuid;
+
+/// Candles of one series keyed by bar time, plus the ranges known to be fully cached.
+#[derive(Default)]
+struct CachedSeries {
+    candles: BTreeMap<i64, Candle>,
+    ranges: Vec<(i64, i64)>,
+}
+
+/// Repositories kept in process memory, so handlers can be exercised without MySQL.
+/// Broker tokens are stored as given; there is nothing at rest to encrypt.
+#[derive(Default)]
+pub struct InMemoryDatabase {
+    users: Mutex<HashMap<String, GetUserAuthByEmail>>,
+    broker_connections: Mutex<HashMap<(Uuid, String), BrokerConnection>>,
+    candles: Mutex<HashMap<CandleSeries, CachedSeries>>,
+    trades: Mutex<Vec<Trade>>,
+}
+
+#[async_trait]
+impl UserRepository for InMemoryDatabase {
+    async fn create_user_and_user_auth(&self, user: CreateUser, user_auth: CreateUserAuth) -> Result<(), DatabaseError> {
+        let mut users = self.users.lock().unwrap();
+        if users.contains_key(&user.email) {
+            return Err(DatabaseError::Conflict(format!("Duplicate entry '{}' for key 'users_email'", user.email)));
+        }
+        let now = chrono::Utc::now().naive_utc();
+        let user_id = Uuid::new_v4();
+        users.insert(
+            user.email.clone(),
+            GetUserAuthByEmail {
+                user_id,
+                password_hash: user_auth.password_hash,
+                created_at: now,
+                updated_at: now,
+                id: user_id,
+                email: user.email,
+                user_created_at: now,
+                user_updated_at: now,
+            },
+        );
+        Ok(())
+    }
+
+    async fn get_user_by_email(&self, email: &str) -> Result<Option<User>, DatabaseError> {
+        Ok(self.users.lock().unwrap().get(email).map(|user_auth| User {
+            id: user_auth.id,
+            email: user_auth.email.clone(),
+            created_at: user_auth.user_created_at,
+            updated_at: user_auth.user_updated_at,
+        }))
+    }
+
+    async fn get_user_auth_by_email(&self, email: &str) -> Result<Option<GetUserAuthByEmail>, DatabaseError> {
+        Ok(self.users.lock().unwrap().get(email).cloned())
+    }
+}
+
+#[async_trait]
+impl BrokerConnectionRepository for InMemoryDatabase {
+    async fn upsert_broker_connection(&self, connection: UpsertBrokerConnection) -> Result<(), DatabaseError> {
+        let now = chrono::Utc::now().naive_utc();
+        let mut broker_connections = self.broker_connections.lock().unwrap();
+        let created_at = broker_connections.get(&(connection.user_id, connection.broker.clone())).map_or(now, |existing| existing.created_at);
+        broker_connections.insert(
+            (connection.user_id, connection.broker.clone()),
+            BrokerConnection {
+                user_id: connection.user_id,
+                broker: connection.broker,
+                access_token: connection.access_token,
+                refresh_token: connection.refresh_token,
+                access_token_expires_at: connection.access_token_expires_at,
+                refresh_token_expires_at: connection.refresh_token_expires_at,
+                created_at,
+                updated_at: now,
+            },
+        );
+        Ok(())
+    }
+
+    async fn get_broker_connection(&self, user_id: Uuid, broker: &str) -> Result<Option<BrokerConnection>, DatabaseError> {
+        Ok(self.broker_connections.lock().unwrap().get(&(user_id, broker.to_string())).cloned())
+    }
+}
+
+#[async_trait]
+impl CandleRepository for InMemoryDatabase {
+    async fn get_cached_candles(&self, series: &CandleSeries, start: i64, end: i64) -> Result<Option<Vec<Candle>>, DatabaseError> {
+        let candles = self.candles.lock().unwrap();
+        let cached = match candles.get(series) {
+            Some(cached) => cached,
+            None => return Ok(None),
+        };
+        if !cached.ranges.iter().any(|(range_start, range_end)| *range_start <= start && *range_end >= end) {
+            return Ok(None);
+        }
+        Ok(Some(cached.candles.range(start..=end).map(|(_, candle)| candle.clone()).collect()))
+    }
+
+    async fn cache_candles(&self, series: &CandleSeries, start: i64, end: i64, candles: Vec<Candle>) -> Result<(), DatabaseError> {
+        let mut cached = self.candles.lock().unwrap();
+        let cached = cached.entry(series.clone()).or_default();
+        cached.candles.extend(candles.into_iter().map(|candle| (candle.datetime, candle)));
+        cached.ranges.push((start, end));
+        Ok(())
+    }
+}
+
+fn matches(filter: &TradeFilter, trade: &Trade) -> bool {
+    let filled_on = trade.first_filled_at.date();
+    filter.account_id.is_none_or(|account_id| trade.account_id == account_id)
+        && filter.symbol.as_ref().is_none_or(|symbol| &trade.symbol == symbol)
+        && filter.setup.as_ref().is_none_or(|setup| trade.setup.as_ref() == Some(setup))
+        && filter.tag.as_ref().is_none_or(|tag| trade.tags.contains(tag))
+        && filter.from.is_none_or(|from| filled_on >= from)
+        && filter.to.is_none_or(|to| filled_on <= to)
+}
+
+#[async_trait]
+impl TradeRepository for InMemoryDatabase {
+    async fn upsert_trades(&self, upserts: Vec<UpsertTrade>) -> Result<(), DatabaseError> {
+        let now = chrono::Utc::now().naive_utc();
+        let mut trades = self.trades.lock().unwrap();
+        for upsert in upserts {
+            let index = match trades
+                .iter()
+                .position(|trade| trade.user_id == upsert.user_id && trade.order_id == upsert.order_id && trade.leg_id == upsert.leg_id)
+            {
+                Some(index) => index,
+                None => {
+                    trades.push(Trade {
+                        id: Uuid::new_v4(),
+                        user_id: upsert.user_id,
+                        account_id: upsert.account_id,
+                        order_id: upsert.order_id,
+                        leg_id: upsert.leg_id,
+                        symbol: String::new(),
+                        asset_type: String::new(),
+                        instruction: String::new(),
+                        position_effect: None,
+                        quantity: 0.0,
+                        price: 0.0,
+                        first_filled_at: now,
+                        last_filled_at: now,
+                        notes: String::new(),
+                        tags: vec![],
+                        setup: None,
+                        screenshots: vec![],
+                        executions: vec![],
+                        created_at: now,
+                        updated_at: now,
+                    });
+                    trades.len() - 1
+                }
+            };
+            let trade = &mut trades[index];
+            trade.account_id = upsert.account_id;
+            trade.symbol = upsert.symbol;
+            trade.asset_type = upsert.asset_type;
+            trade.instruction = upsert.instruction;
+            trade.position_effect = upsert.position_effect;
+            trade.quantity = upsert.quantity;
+            trade.price = upsert.price;
+            trade.first_filled_at = upsert.first_filled_at;
+            trade.last_filled_at = upsert.last_filled_at;
+            trade.updated_at = now;
+            for execution in upsert.executions {
+                match trade.executions.iter_mut().find(|existing| existing.sequence == execution.sequence) {
+                    Some(existing) => *existing = execution,
+                    None => trade.executions.push(execution),
+                }
+            }
+            trade.executions.sort_by_key(|execution| execution.sequence);
+        }
+        Ok(())
+    }
+
+    async fn get_trades(&self, user_id: Uuid, filter: &TradeFilter) -> Result<Vec<Trade>, DatabaseError> {
+        let mut trades: Vec<Trade> = self.trades.lock().unwrap().iter().filter(|trade| trade.user_id == user_id && matches(filter, trade)).cloned().collect();
+        trades.sort_by(|a, b| (b.first_filled_at, b.order_id, a.leg_id).cmp(&(a.first_filled_at, a.order_id, b.leg_id)));
+        Ok(trades)
+    }
+
+    async fn get_trade(&self, user_id: Uuid, trade_id: Uuid) -> Result<Option<Trade>, DatabaseError> {
+        Ok(self.trades.lock().unwrap().iter().find(|trade| trade.id == trade_id && trade.user_id == user_id).cloned())
+    }
+
+    async fn annotate_trade(&self, user_id: Uuid, trade_id: Uuid, annotation: TradeAnnotation) -> Result<Option<Trade>, DatabaseError> {
+        let mut trades = self.trades.lock().unwrap();
+        let trade = match trades.iter_mut().find(|trade| trade.id == trade_id && trade.user_id == user_id) {
+            Some(trade) => trade,
+            None => return Ok(None),
+        };
+        annotation.apply(trade);
+        trade.updated_at = chrono::Utc::now().naive_utc();
+        Ok(Some(trade.clone()))
+    }
+}
@@ -0,0 +1,158 @@
+use super::{error::DatabaseError, DatabaseClient};
+use chrono::NaiveDateTime;
+use log::info;
+use mysql::{params, prelude::Queryable, PooledConn};
+use std::io;
+
+pub struct Migration {
+    pub version: u32,
+    pub name: &'static str,
+    pub up: &'static str,
+    pub down: &'static str,
+}
+
+macro_rules! migration {
+    ($version:expr, $name:literal) => {
+        Migration {
+            version: $version,
+            name: $name,
+            up: include_str!(concat!("../../migrations/", $name, ".up.sql")),
+            down: include_str!(concat!("../../migrations/", $name, ".down.sql")),
+        }
+    };
+}
+
+/// Every migration in `migrations/`, in the order they are applied.
+pub const MIGRATIONS: &[Migration] = &[
+    migration!(1, "0001_create_users"),
+    migration!(2, "0002_create_user_auth"),
+    migration!(3, "0003_create_broker_connections"),
+    migration!(4, "0004_create_candles"),
+    migration!(5, "0005_create_candle_ranges"),
+    migration!(6, "0006_create_trades"),
+    migration!(7, "0007_create_executions"),
+];
+
+/// Named lock held while migrating, so instances starting together apply each migration once.
+const MIGRATION_LOCK: &str = "schema_migrations";
+const MIGRATION_LOCK_TIMEOUT_SECS: u32 = 60;
+
+const CREATE_SCHEMA_MIGRATIONS: &str = "CREATE TABLE IF NOT EXISTS schema_migrations (version INT UNSIGNED NOT NULL, name VARCHAR(255) NOT NULL, applied_at DATETIME NOT NULL, PRIMARY KEY (version))";
+
+pub struct MigrationStatus {
+    pub version: u32,
+    pub name: &'static str,
+    pub applied_at: Option<NaiveDateTime>,
+}
+
+fn pending(applied: &[u32]) -> impl Iterator<Item = &'static Migration> + '_ {
+    MIGRATIONS.iter().filter(move |migration| !applied.contains(&migration.version))
+}
+
+fn applied_migrations(conn: &mut PooledConn) -> Result<Vec<(u32, NaiveDateTime)>, mysql::Error> {
+    conn.query_drop(CREATE_SCHEMA_MIGRATIONS)?;
+    conn.query("SELECT version, applied_at FROM schema_migrations ORDER BY version")
+}
+
+/// Runs `f` holding [`MIGRATION_LOCK`]. Migrations are DDL, which MySQL can't roll back,
+/// so a second instance has to wait rather than race on the same pending versions.
+fn with_migration_lock<T>(conn: &mut PooledConn, f: impl FnOnce(&mut PooledConn) -> Result<T, mysql::Error>) -> Result<T, mysql::Error> {
+    let lock_params = params! {"name" => MIGRATION_LOCK, "timeout" => MIGRATION_LOCK_TIMEOUT_SECS};
+    let acquired = conn.exec_first::<Option<i64>, _, _>("SELECT GET_LOCK(:name, :timeout)", lock_params)?.flatten();
+    if acquired != Some(1) {
+        let message = format!("timed out waiting for the {} lock", MIGRATION_LOCK);
+        return Err(mysql::Error::IoError(io::Error::new(io::ErrorKind::TimedOut, message)));
+    }
+    let result = f(conn);
+    // Named locks belong to the session, which goes back to the pool rather than closing.
+    let released = conn.exec_drop("SELECT RELEASE_LOCK(:name)", params! {"name" => MIGRATION_LOCK});
+    let value = result?;
+    released?;
+    Ok(value)
+}
+
+fn apply_pending(conn: &mut PooledConn) -> Result<Vec<u32>, mysql::Error> {
+    let applied: Vec<u32> = applied_migrations(conn)?.into_iter().map(|(version, _)| version).collect();
+    let mut migrated = vec![];
+    for migration in pending(&applied) {
+        info!("Applying migration {}", migration.name);
+        // MySQL commits DDL implicitly, so a failed migration is not rolled back.
+        conn.query_drop(migration.up)?;
+        conn.exec_drop(
+            "INSERT INTO schema_migrations (version, name, applied_at) VALUES (:version, :name, :applied_at)",
+            params! {
+                "version" => migration.version,
+                "name" => migration.name,
+                "applied_at" => chrono::Utc::now().naive_utc(),
+            },
+        )?;
+        migrated.push(migration.version);
+    }
+    Ok(migrated)
+}
+
+fn revert_latest(conn: &mut PooledConn) -> Result<Option<u32>, mysql::Error> {
+    let latest = applied_migrations(conn)?.into_iter().map(|(version, _)| version).max();
+    let migration = match latest.and_then(|version| MIGRATIONS.iter().find(|migration| migration.version == version)) {
+        Some(migration) => migration,
+        None => return Ok(None),
+    };
+    info!("Reverting migration {}", migration.name);
+    conn.query_drop(migration.down)?;
+    conn.exec_drop("DELETE FROM schema_migrations WHERE version = :version", params! {"version" => migration.version})?;
+    Ok(Some(migration.version))
+}
+
+impl DatabaseClient {
+    /// Applies every pending migration and returns the versions that were applied.
+    pub fn migrate_up(&self) -> Result<Vec<u32>, mysql::Error> {
+        let mut conn = self.client.try_get_conn(self.acquire_timeout_ms)?;
+        with_migration_lock(&mut conn, apply_pending)
+    }
+
+    /// [`Self::migrate_up`] on tokio's blocking pool, for callers already inside the runtime.
+    pub async fn migrate_up_async(&self) -> Result<Vec<u32>, DatabaseError> {
+        self.run(|conn| Ok(with_migration_lock(conn, apply_pending)?)).await
+    }
+
+    /// Reverts the most recently applied migration, if any, and returns its version.
+    pub fn migrate_down(&self) -> Result<Option<u32>, mysql::Error> {
+        let mut conn = self.client.try_get_conn(self.acquire_timeout_ms)?;
+        with_migration_lock(&mut conn, revert_latest)
+    }
+
+    pub fn migration_status(&self) -> Result<Vec<MigrationStatus>, mysql::Error> {
+        let mut conn = self.client.try_get_conn(self.acquire_timeout_ms)?;
+        let applied = applied_migrations(&mut conn)?;
+        Ok(MIGRATIONS
+            .iter()
+            .map(|migration| MigrationStatus {
+                version: migration.version,
+                name: migration.name,
+                applied_at: applied.iter().find(|(version, _)| *version == migration.version).map(|(_, applied_at)| *applied_at),
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{pending, MIGRATIONS};
+
+    #[test]
+    fn test_migrations_are_sequential() {
+        for (index, migration) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(migration.version as usize, index + 1);
+            assert!(migration.name.starts_with(&format!("{:04}_", migration.version)));
+            assert!(!migration.up.trim().is_empty());
+            assert!(!migration.down.trim().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_pending_skips_applied_migrations() {
+        let pending: Vec<u32> = pending(&[1, 2]).map(|migration| migration.version).collect();
+        assert_eq!(pending, vec![3, 4, 5, 6, 7]);
+        assert_eq!(super::pending(&[]).count(), MIGRATIONS.len());
+    }
+}
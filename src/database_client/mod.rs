@@ -1,19 +1,33 @@
-use chrono::NaiveDateTime;
-use log::error;
+use crate::tda_client::price_history::{Candle, FrequencyType};
+use chrono::{NaiveDate, NaiveDateTime};
+use error::DatabaseError;
+use models::parse_uuid;
 use mysql::{
     params,
     prelude::{FromRow, Queryable},
+    PooledConn, TxOpts,
 };
-use std::env;
+use std::{collections::HashMap, env, str::FromStr, time::Duration};
 use uuid::Uuid;
 
+pub mod error;
+pub mod memory;
+pub mod migrations;
 pub mod models;
+pub mod repository;
 
 #[derive(Clone)]
 pub struct DatabaseClient {
     client: mysql::Pool,
+    acquire_timeout_ms: u32,
 }
 
+const DEFAULT_POOL_MIN: usize = 1;
+const DEFAULT_POOL_MAX: usize = 10;
+const DEFAULT_ACQUIRE_TIMEOUT_MS: u32 = 5000;
+
+pub const BROKER_TDA: &str = "tda";
+
 pub struct CreateUser {
     pub email: String,
 }
@@ -22,6 +36,92 @@ pub struct CreateUserAuth {
     pub password_hash: String,
 }
 
+pub struct UpsertBrokerConnection {
+    pub user_id: Uuid,
+    pub broker: String,
+    pub access_token: String,
+    pub refresh_token: String,
+    pub access_token_expires_at: NaiveDateTime,
+    pub refresh_token_expires_at: NaiveDateTime,
+}
+
+/// Identifies one cached candle series: the same symbol at another frequency, or with
+/// extended hours, is a separate series.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CandleSeries {
+    pub symbol: String,
+    pub frequency_type: FrequencyType,
+    pub frequency: u32,
+    pub extended_hours: bool,
+}
+
+/// A filled order leg to record in the journal. Trades are keyed by user, order id and
+/// leg id, so syncing the same order again updates the trade instead of adding one.
+pub struct UpsertTrade {
+    pub user_id: Uuid,
+    pub account_id: i64,
+    pub order_id: i64,
+    pub leg_id: i64,
+    pub symbol: String,
+    pub asset_type: String,
+    pub instruction: String,
+    pub position_effect: Option<String>,
+    pub quantity: f64,
+    pub price: f64,
+    pub first_filled_at: NaiveDateTime,
+    pub last_filled_at: NaiveDateTime,
+    pub executions: Vec<models::TradeExecution>,
+}
+
+/// Narrows a journal listing; every field left out matches all trades. `from` and `to`
+/// are inclusive and compare against the trade's first fill.
+#[derive(Clone, Debug, Default, serde::Deserialize)]
+pub struct TradeFilter {
+    pub account_id: Option<i64>,
+    pub symbol: Option<String>,
+    pub tag: Option<String>,
+    pub setup: Option<String>,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+/// Changes to a trade's annotations. Fields left out keep their value; an empty `setup`
+/// clears it.
+#[derive(Clone, Debug, Default, serde::Deserialize)]
+pub struct TradeAnnotation {
+    pub notes: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub setup: Option<String>,
+    pub screenshots: Option<Vec<String>>,
+}
+
+impl TradeAnnotation {
+    pub fn apply(self, trade: &mut models::Trade) {
+        if let Some(notes) = self.notes {
+            trade.notes = notes;
+        }
+        if let Some(tags) = self.tags {
+            trade.tags = vec![];
+            for tag in tags.iter().map(|tag| tag.trim()).filter(|tag| !tag.is_empty()) {
+                if !trade.tags.iter().any(|existing| existing == tag) {
+                    trade.tags.push(tag.to_string());
+                }
+            }
+        }
+        if let Some(setup) = self.setup {
+            let setup = setup.trim();
+            trade.setup = (!setup.is_empty()).then(|| setup.to_string());
+        }
+        if let Some(screenshots) = self.screenshots {
+            trade.screenshots = screenshots;
+        }
+    }
+}
+
+const TRADE_COLUMNS: &str = "trades.id, trades.user_id, trades.account_id, trades.order_id, trades.leg_id, trades.symbol, trades.asset_type, trades.instruction, trades.position_effect, trades.quantity, trades.price, trades.first_filled_at, trades.last_filled_at, trades.notes, trades.tags, trades.setup, trades.screenshots, trades.created_at, trades.updated_at";
+
+const TRADE_FILTER: &str = "trades.user_id = :user_id AND (:account_id IS NULL OR trades.account_id = :account_id) AND (:symbol IS NULL OR trades.symbol = :symbol) AND (:setup IS NULL OR trades.setup = :setup) AND (:tag IS NULL OR JSON_CONTAINS(trades.tags, JSON_QUOTE(:tag))) AND (:from IS NULL OR trades.first_filled_at >= :from) AND (:to IS NULL OR trades.first_filled_at < :to)";
+
 #[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
 pub struct GetUserAuthByEmail {
     pub user_id: Uuid,
@@ -39,18 +139,7 @@ impl FromRow for GetUserAuthByEmail {
     where
         Self: Sized,
     {
-        let (user_id, password_hash, created_at, updated_at, id, email, user_created_at, user_updated_at) =
-            mysql::from_row::<(String, String, NaiveDateTime, NaiveDateTime, String, String, NaiveDateTime, NaiveDateTime)>(row);
-        GetUserAuthByEmail {
-            user_id: Uuid::parse_str(&user_id).expect("Error converting user_id to Uuid"),
-            password_hash,
-            created_at,
-            updated_at,
-            id: Uuid::parse_str(&id).expect("Error converting id to Uuid"),
-            email,
-            user_created_at,
-            user_updated_at,
-        }
+        Self::from_row_opt(row).expect("Error converting row to GetUserAuthByEmail")
     }
 
     fn from_row_opt(row: mysql::Row) -> Result<Self, mysql::FromRowError>
@@ -58,13 +147,13 @@ impl FromRow for GetUserAuthByEmail {
         Self: Sized,
     {
         let (user_id, password_hash, created_at, updated_at, id, email, user_created_at, user_updated_at) =
-            mysql::from_row_opt::<(String, String, NaiveDateTime, NaiveDateTime, String, String, NaiveDateTime, NaiveDateTime)>(row)?;
+            mysql::from_row_opt::<(String, String, NaiveDateTime, NaiveDateTime, String, String, NaiveDateTime, NaiveDateTime)>(row.clone())?;
         Ok(GetUserAuthByEmail {
-            user_id: Uuid::parse_str(&user_id).expect("Error converting user_id to Uuid"),
+            user_id: parse_uuid(&user_id, &row)?,
             password_hash,
             created_at,
             updated_at,
-            id: Uuid::parse_str(&id).expect("Error converting id to Uuid"),
+            id: parse_uuid(&id, &row)?,
             email,
             user_created_at,
             user_updated_at,
@@ -72,62 +161,373 @@ impl FromRow for GetUserAuthByEmail {
     }
 }
 
+impl Default for DatabaseClient {
+    fn default() -> Self {
+        DatabaseClient::new()
+    }
+}
+
 impl DatabaseClient {
-    pub fn create_user_and_user_auth(&self, user: CreateUser, user_auth: CreateUserAuth) -> Result<(), mysql::Error> {
-        let mut conn = self.client.get_conn().unwrap();
-        let now = chrono::Utc::now().naive_utc();
-        let new_user_id = Uuid::new_v4();
-        let insert_user = conn.exec_drop(
-            "INSERT INTO users (id, email, created_at, updated_at) VALUES (:id, :email, :created_at, :updated_at)",
-            params! {
-                "id" => new_user_id.to_string(),
-                "email" => user.email.clone(),
-                "created_at" => now,
-                "updated_at" => now,
-            },
-        );
-        if insert_user.is_err() {
-            let error = insert_user.err().unwrap();
-            error!("Error creating user: {:?}", error);
-            return Err(error);
-        }
-        let now = chrono::Utc::now().naive_utc();
-        conn.exec_drop(
-            "INSERT INTO user_auth (user_id, password_hash, created_at, updated_at) VALUES (:user_id, :password_hash, :created_at, :updated_at)",
-            params! {
-                "user_id" => new_user_id.to_string(),
-                "password_hash" => user_auth.password_hash,
-                "created_at" => now,
-                "updated_at" => now,
-            },
-        )
-    }
-
-    pub fn get_user_by_email(&self, email: &str) -> Option<models::User> {
-        let mut conn = self.client.get_conn().unwrap();
-        let result = conn.exec_first::<models::User, _, _>("SELECT * FROM users WHERE email = :email", params! {"email" => email});
-        match result {
-            Ok(user) => user,
-            Err(_) => None,
-        }
+    /// Runs `f` on tokio's blocking pool with a pooled connection, so the synchronous
+    /// `mysql` driver never blocks the async workers.
+    async fn run<T, F>(&self, f: F) -> Result<T, DatabaseError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut PooledConn) -> Result<T, DatabaseError> + Send + 'static,
+    {
+        let pool = self.client.clone();
+        let acquire_timeout_ms = self.acquire_timeout_ms;
+        tokio::task::spawn_blocking(move || {
+            let mut conn = pool.try_get_conn(acquire_timeout_ms)?;
+            f(&mut conn)
+        })
+        .await?
     }
 
-    pub fn get_user_auth_by_email(&self, email: &str) -> Option<GetUserAuthByEmail> {
-        let mut conn = self.client.get_conn().unwrap();
-        let result = conn.exec_first::<GetUserAuthByEmail, _, _>(
-            "SELECT user_auth.user_id, user_auth.password_hash, user_auth.created_at as user_auth_created_at, user_auth.updated_at as user_auth_updated_at, users.id, users.email, users.created_at as user_created_at, users.updated_at as user_updated_at FROM user_auth INNER JOIN users ON users.id = user_auth.user_id WHERE users.email = :email",
-            params! {"email" => email},
-        );
-        match result {
-            Ok(user) => user,
-            Err(_) => None,
-        }
+    pub async fn create_user_and_user_auth(&self, user: CreateUser, user_auth: CreateUserAuth) -> Result<(), DatabaseError> {
+        self.run(move |conn| {
+            let now = chrono::Utc::now().naive_utc();
+            let new_user_id = Uuid::new_v4();
+            let mut tx = conn.start_transaction(TxOpts::default())?;
+            tx.exec_drop(
+                "INSERT INTO users (id, email, created_at, updated_at) VALUES (:id, :email, :created_at, :updated_at)",
+                params! {
+                    "id" => new_user_id.to_string(),
+                    "email" => user.email,
+                    "created_at" => now,
+                    "updated_at" => now,
+                },
+            )?;
+            tx.exec_drop(
+                "INSERT INTO user_auth (user_id, password_hash, created_at, updated_at) VALUES (:user_id, :password_hash, :created_at, :updated_at)",
+                params! {
+                    "user_id" => new_user_id.to_string(),
+                    "password_hash" => user_auth.password_hash,
+                    "created_at" => now,
+                    "updated_at" => now,
+                },
+            )?;
+            tx.commit()?;
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn get_user_by_email(&self, email: &str) -> Result<Option<models::User>, DatabaseError> {
+        let email = email.to_string();
+        self.run(move |conn| {
+            let user = conn.exec_first::<models::User, _, _>("SELECT id, email, created_at, updated_at FROM users WHERE email = :email", params! {"email" => email})?;
+            Ok(user)
+        })
+        .await
+    }
+
+    pub async fn get_user_auth_by_email(&self, email: &str) -> Result<Option<GetUserAuthByEmail>, DatabaseError> {
+        let email = email.to_string();
+        self.run(move |conn| {
+            let user_auth = conn.exec_first::<GetUserAuthByEmail, _, _>(
+                "SELECT user_auth.user_id, user_auth.password_hash, user_auth.created_at as user_auth_created_at, user_auth.updated_at as user_auth_updated_at, users.id, users.email, users.created_at as user_created_at, users.updated_at as user_updated_at FROM user_auth INNER JOIN users ON users.id = user_auth.user_id WHERE users.email = :email",
+                params! {"email" => email},
+            )?;
+            Ok(user_auth)
+        })
+        .await
+    }
+
+    /// Stores the tokens as given; [`repository::EncryptedBrokerConnections`] encrypts them first.
+    pub async fn upsert_broker_connection(&self, connection: UpsertBrokerConnection) -> Result<(), DatabaseError> {
+        self.run(move |conn| {
+            let now = chrono::Utc::now().naive_utc();
+            conn.exec_drop(
+                "INSERT INTO broker_connections (user_id, broker, access_token, refresh_token, access_token_expires_at, refresh_token_expires_at, created_at, updated_at) VALUES (:user_id, :broker, :access_token, :refresh_token, :access_token_expires_at, :refresh_token_expires_at, :created_at, :updated_at) ON DUPLICATE KEY UPDATE access_token = VALUES(access_token), refresh_token = VALUES(refresh_token), access_token_expires_at = VALUES(access_token_expires_at), refresh_token_expires_at = VALUES(refresh_token_expires_at), updated_at = VALUES(updated_at)",
+                params! {
+                    "user_id" => connection.user_id.to_string(),
+                    "broker" => connection.broker,
+                    "access_token" => connection.access_token,
+                    "refresh_token" => connection.refresh_token,
+                    "access_token_expires_at" => connection.access_token_expires_at,
+                    "refresh_token_expires_at" => connection.refresh_token_expires_at,
+                    "created_at" => now,
+                    "updated_at" => now,
+                },
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn get_broker_connection(&self, user_id: Uuid, broker: &str) -> Result<Option<models::BrokerConnection>, DatabaseError> {
+        let broker = broker.to_string();
+        self.run(move |conn| {
+            let connection = conn.exec_first::<models::BrokerConnection, _, _>(
+                "SELECT user_id, broker, access_token, refresh_token, access_token_expires_at, refresh_token_expires_at, created_at, updated_at FROM broker_connections WHERE user_id = :user_id AND broker = :broker",
+                params! {"user_id" => user_id.to_string(), "broker" => broker},
+            )?;
+            Ok(connection)
+        })
+        .await
+    }
+
+    /// Returns the series' candles between `start` and `end` (inclusive, epoch milliseconds)
+    /// if a previously cached range covers them, or `None` if TDA has to be asked.
+    pub async fn get_cached_candles(&self, series: &CandleSeries, start: i64, end: i64) -> Result<Option<Vec<Candle>>, DatabaseError> {
+        let series = series.clone();
+        self.run(move |conn| {
+            let series_params = params! {
+                "symbol" => &series.symbol,
+                "frequency_type" => series.frequency_type.as_str(),
+                "frequency" => series.frequency,
+                "extended_hours" => series.extended_hours,
+                "start" => start,
+                "end" => end,
+            };
+            let covered = conn.exec_first::<u8, _, _>(
+                "SELECT 1 FROM candle_ranges WHERE symbol = :symbol AND frequency_type = :frequency_type AND frequency = :frequency AND extended_hours = :extended_hours AND range_start <= :start AND range_end >= :end LIMIT 1",
+                series_params.clone(),
+            )?;
+            if covered.is_none() {
+                return Ok(None);
+            }
+            let candles = conn.exec_map(
+                "SELECT datetime, open, high, low, close, volume FROM candles WHERE symbol = :symbol AND frequency_type = :frequency_type AND frequency = :frequency AND extended_hours = :extended_hours AND datetime BETWEEN :start AND :end ORDER BY datetime",
+                series_params,
+                |(datetime, open, high, low, close, volume)| Candle {
+                    open,
+                    high,
+                    low,
+                    close,
+                    volume,
+                    datetime,
+                },
+            )?;
+            Ok(Some(candles))
+        })
+        .await
+    }
+
+    /// Stores completed candles and records that `start..=end` of the series is now cached.
+    pub async fn cache_candles(&self, series: &CandleSeries, start: i64, end: i64, candles: Vec<Candle>) -> Result<(), DatabaseError> {
+        let series = series.clone();
+        self.run(move |conn| {
+            let mut tx = conn.start_transaction(TxOpts::default())?;
+            tx.exec_batch(
+                "INSERT INTO candles (symbol, frequency_type, frequency, extended_hours, datetime, open, high, low, close, volume) VALUES (:symbol, :frequency_type, :frequency, :extended_hours, :datetime, :open, :high, :low, :close, :volume) ON DUPLICATE KEY UPDATE open = VALUES(open), high = VALUES(high), low = VALUES(low), close = VALUES(close), volume = VALUES(volume)",
+                candles.iter().map(|candle| {
+                    params! {
+                        "symbol" => &series.symbol,
+                        "frequency_type" => series.frequency_type.as_str(),
+                        "frequency" => series.frequency,
+                        "extended_hours" => series.extended_hours,
+                        "datetime" => candle.datetime,
+                        "open" => candle.open,
+                        "high" => candle.high,
+                        "low" => candle.low,
+                        "close" => candle.close,
+                        "volume" => candle.volume,
+                    }
+                }),
+            )?;
+            tx.exec_drop(
+                "INSERT INTO candle_ranges (symbol, frequency_type, frequency, extended_hours, range_start, range_end, created_at) VALUES (:symbol, :frequency_type, :frequency, :extended_hours, :range_start, :range_end, :created_at)",
+                params! {
+                    "symbol" => &series.symbol,
+                    "frequency_type" => series.frequency_type.as_str(),
+                    "frequency" => series.frequency,
+                    "extended_hours" => series.extended_hours,
+                    "range_start" => start,
+                    "range_end" => end,
+                    "created_at" => chrono::Utc::now().naive_utc(),
+                },
+            )?;
+            tx.commit()?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Records filled legs, refreshing the fill details of trades already in the journal
+    /// while leaving their annotations alone.
+    pub async fn upsert_trades(&self, trades: Vec<UpsertTrade>) -> Result<(), DatabaseError> {
+        self.run(move |conn| {
+            let now = chrono::Utc::now().naive_utc();
+            let mut tx = conn.start_transaction(TxOpts::default())?;
+            for trade in trades {
+                let key = params! {
+                    "user_id" => trade.user_id.to_string(),
+                    "order_id" => trade.order_id,
+                    "leg_id" => trade.leg_id,
+                };
+                tx.exec_drop(
+                    "INSERT INTO trades (id, user_id, account_id, order_id, leg_id, symbol, asset_type, instruction, position_effect, quantity, price, first_filled_at, last_filled_at, notes, tags, setup, screenshots, created_at, updated_at) VALUES (:id, :user_id, :account_id, :order_id, :leg_id, :symbol, :asset_type, :instruction, :position_effect, :quantity, :price, :first_filled_at, :last_filled_at, '', JSON_ARRAY(), NULL, JSON_ARRAY(), :created_at, :updated_at) ON DUPLICATE KEY UPDATE account_id = VALUES(account_id), symbol = VALUES(symbol), asset_type = VALUES(asset_type), instruction = VALUES(instruction), position_effect = VALUES(position_effect), quantity = VALUES(quantity), price = VALUES(price), first_filled_at = VALUES(first_filled_at), last_filled_at = VALUES(last_filled_at), updated_at = VALUES(updated_at)",
+                    params! {
+                        "id" => Uuid::new_v4().to_string(),
+                        "user_id" => trade.user_id.to_string(),
+                        "account_id" => trade.account_id,
+                        "order_id" => trade.order_id,
+                        "leg_id" => trade.leg_id,
+                        "symbol" => &trade.symbol,
+                        "asset_type" => &trade.asset_type,
+                        "instruction" => &trade.instruction,
+                        "position_effect" => &trade.position_effect,
+                        "quantity" => trade.quantity,
+                        "price" => trade.price,
+                        "first_filled_at" => trade.first_filled_at,
+                        "last_filled_at" => trade.last_filled_at,
+                        "created_at" => now,
+                        "updated_at" => now,
+                    },
+                )?;
+                let trade_id = tx
+                    .exec_first::<String, _, _>("SELECT id FROM trades WHERE user_id = :user_id AND order_id = :order_id AND leg_id = :leg_id", key)?
+                    .ok_or_else(|| DatabaseError::MissingRow(format!("trade for order {} leg {} after upsert", trade.order_id, trade.leg_id)))?;
+                tx.exec_batch(
+                    "INSERT INTO executions (trade_id, sequence, user_id, activity_id, quantity, price, filled_at) VALUES (:trade_id, :sequence, :user_id, :activity_id, :quantity, :price, :filled_at) ON DUPLICATE KEY UPDATE activity_id = VALUES(activity_id), quantity = VALUES(quantity), price = VALUES(price), filled_at = VALUES(filled_at)",
+                    trade.executions.iter().map(|execution| {
+                        params! {
+                            "trade_id" => &trade_id,
+                            "sequence" => execution.sequence,
+                            "user_id" => trade.user_id.to_string(),
+                            "activity_id" => execution.activity_id,
+                            "quantity" => execution.quantity,
+                            "price" => execution.price,
+                            "filled_at" => execution.filled_at,
+                        }
+                    }),
+                )?;
+            }
+            tx.commit()?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// The user's trades matching `filter`, most recent first.
+    pub async fn get_trades(&self, user_id: Uuid, filter: &TradeFilter) -> Result<Vec<models::Trade>, DatabaseError> {
+        let filter = filter.clone();
+        self.run(move |conn| {
+            let filter_params = params! {
+                "user_id" => user_id.to_string(),
+                "account_id" => filter.account_id,
+                "symbol" => filter.symbol,
+                "setup" => filter.setup,
+                "tag" => filter.tag,
+                "from" => filter.from.map(|from| from.and_hms_opt(0, 0, 0).unwrap()),
+                "to" => filter.to.and_then(|to| to.succ_opt()).map(|to| to.and_hms_opt(0, 0, 0).unwrap()),
+            };
+            let trades = conn.exec::<models::Trade, _, _>(
+                format!("SELECT {} FROM trades WHERE {} ORDER BY trades.first_filled_at DESC, trades.order_id DESC, trades.leg_id", TRADE_COLUMNS, TRADE_FILTER),
+                filter_params.clone(),
+            )?;
+            let executions = conn.exec_map(
+                format!(
+                    "SELECT executions.trade_id, executions.sequence, executions.activity_id, executions.quantity, executions.price, executions.filled_at FROM executions INNER JOIN trades ON trades.id = executions.trade_id WHERE {} ORDER BY executions.sequence",
+                    TRADE_FILTER
+                ),
+                filter_params,
+                |(trade_id, sequence, activity_id, quantity, price, filled_at): (String, u32, Option<i64>, f64, f64, NaiveDateTime)| {
+                    (
+                        trade_id,
+                        models::TradeExecution {
+                            sequence,
+                            activity_id,
+                            quantity,
+                            price,
+                            filled_at,
+                        },
+                    )
+                },
+            )?;
+            Ok(with_executions(trades, executions))
+        })
+        .await
+    }
+
+    pub async fn get_trade(&self, user_id: Uuid, trade_id: Uuid) -> Result<Option<models::Trade>, DatabaseError> {
+        self.run(move |conn| get_trade(conn, user_id, trade_id, false)).await
+    }
+
+    /// Returns the annotated trade, or `None` if the user has no such trade. The trade row
+    /// stays locked from read to write so concurrent annotations don't overwrite each other.
+    pub async fn annotate_trade(&self, user_id: Uuid, trade_id: Uuid, annotation: TradeAnnotation) -> Result<Option<models::Trade>, DatabaseError> {
+        self.run(move |conn| {
+            let mut tx = conn.start_transaction(TxOpts::default())?;
+            let mut trade = match get_trade(&mut tx, user_id, trade_id, true)? {
+                Some(trade) => trade,
+                None => return Ok(None),
+            };
+            annotation.apply(&mut trade);
+            trade.updated_at = chrono::Utc::now().naive_utc();
+            tx.exec_drop(
+                "UPDATE trades SET notes = :notes, tags = :tags, setup = :setup, screenshots = :screenshots, updated_at = :updated_at WHERE id = :id AND user_id = :user_id",
+                params! {
+                    "notes" => &trade.notes,
+                    "tags" => serde_json::to_string(&trade.tags).unwrap(),
+                    "setup" => &trade.setup,
+                    "screenshots" => serde_json::to_string(&trade.screenshots).unwrap(),
+                    "updated_at" => trade.updated_at,
+                    "id" => trade_id.to_string(),
+                    "user_id" => user_id.to_string(),
+                },
+            )?;
+            tx.commit()?;
+            Ok(Some(trade))
+        })
+        .await
     }
 
     pub fn new() -> Self {
         let url = env::var("DATABASE_URL").expect("DATABASE_URL not found");
-        let builder = mysql::OptsBuilder::from_opts(mysql::Opts::from_url(&url).unwrap());
-        let client = mysql::Pool::new(builder.ssl_opts(mysql::SslOpts::default())).unwrap();
-        Self { client }
+        let pool_min = env_or("DATABASE_POOL_MIN", DEFAULT_POOL_MIN);
+        let pool_max = env_or("DATABASE_POOL_MAX", DEFAULT_POOL_MAX);
+        let acquire_timeout_ms = env_or("DATABASE_ACQUIRE_TIMEOUT_MS", DEFAULT_ACQUIRE_TIMEOUT_MS);
+        let builder = mysql::OptsBuilder::from_opts(mysql::Opts::from_url(&url).unwrap())
+            .ssl_opts(mysql::SslOpts::default())
+            .tcp_connect_timeout(Some(Duration::from_millis(acquire_timeout_ms as u64)));
+        let client = mysql::Pool::new_manual(pool_min, pool_max, builder).expect("Error creating database pool");
+        Self { client, acquire_timeout_ms }
+    }
+}
+
+/// Loads a trade with its executions; `for_update` locks the trade row until the
+/// surrounding transaction ends.
+fn get_trade<Q: Queryable>(conn: &mut Q, user_id: Uuid, trade_id: Uuid, for_update: bool) -> Result<Option<models::Trade>, DatabaseError> {
+    let id_params = params! {"id" => trade_id.to_string(), "user_id" => user_id.to_string()};
+    let lock = if for_update { " FOR UPDATE" } else { "" };
+    let query = format!("SELECT {} FROM trades WHERE trades.id = :id AND trades.user_id = :user_id{}", TRADE_COLUMNS, lock);
+    let trade = conn.exec_first::<models::Trade, _, _>(query, id_params.clone())?;
+    let mut trade = match trade {
+        Some(trade) => trade,
+        None => return Ok(None),
+    };
+    trade.executions = conn.exec_map(
+        "SELECT sequence, activity_id, quantity, price, filled_at FROM executions WHERE trade_id = :id AND user_id = :user_id ORDER BY sequence",
+        id_params,
+        |(sequence, activity_id, quantity, price, filled_at)| models::TradeExecution {
+            sequence,
+            activity_id,
+            quantity,
+            price,
+            filled_at,
+        },
+    )?;
+    Ok(Some(trade))
+}
+
+/// Hands each trade the executions read for it alongside.
+fn with_executions(mut trades: Vec<models::Trade>, executions: Vec<(String, models::TradeExecution)>) -> Vec<models::Trade> {
+    let mut by_trade: HashMap<String, Vec<models::TradeExecution>> = HashMap::new();
+    for (trade_id, execution) in executions {
+        by_trade.entry(trade_id).or_default().push(execution);
+    }
+    for trade in &mut trades {
+        trade.executions = by_trade.remove(&trade.id.to_string()).unwrap_or_default();
+    }
+    trades
+}
+
+fn env_or<T: FromStr>(name: &str, default: T) -> T {
+    match env::var(name) {
+        Ok(value) => value.parse().unwrap_or_else(|_| panic!("{} is invalid", name)),
+        Err(_) => default,
     }
 }
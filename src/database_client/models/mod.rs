@@ -3,6 +3,11 @@ use mysql::prelude::FromRow;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+/// Ids are stored as `CHAR(36)`; a malformed one fails the row rather than panicking.
+pub(crate) fn parse_uuid(id: &str, row: &mysql::Row) -> Result<Uuid, mysql::FromRowError> {
+    Uuid::parse_str(id).map_err(|_| mysql::FromRowError(row.clone()))
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct User {
     pub id: Uuid,
@@ -16,22 +21,16 @@ impl FromRow for User {
     where
         Self: Sized,
     {
-        let (id, email, created_at, updated_at) = mysql::from_row::<(Vec<u8>, String, NaiveDateTime, NaiveDateTime)>(row);
-        User {
-            id: Uuid::from_slice(&id).expect("Error converting id to Uuid"),
-            email,
-            created_at,
-            updated_at,
-        }
+        Self::from_row_opt(row).expect("Error converting row to User")
     }
 
     fn from_row_opt(row: mysql::Row) -> Result<Self, mysql::FromRowError>
     where
         Self: Sized,
     {
-        let (id, email, created_at, updated_at) = mysql::from_row_opt::<(Vec<u8>, String, NaiveDateTime, NaiveDateTime)>(row)?;
+        let (id, email, created_at, updated_at) = mysql::from_row_opt::<(String, String, NaiveDateTime, NaiveDateTime)>(row.clone())?;
         Ok(User {
-            id: Uuid::from_slice(&id).expect("Error converting id to Uuid"),
+            id: parse_uuid(&id, &row)?,
             email,
             created_at,
             updated_at,
@@ -52,22 +51,16 @@ impl FromRow for UserAuth {
     where
         Self: Sized,
     {
-        let (user_id, password_hash, created_at, updated_at) = mysql::from_row::<(Vec<u8>, String, NaiveDateTime, NaiveDateTime)>(row);
-        UserAuth {
-            user_id: Uuid::from_slice(&user_id).expect("Error converting user_id to Uuid"),
-            password_hash,
-            created_at,
-            updated_at,
-        }
+        Self::from_row_opt(row).expect("Error converting row to UserAuth")
     }
 
     fn from_row_opt(row: mysql::Row) -> Result<Self, mysql::FromRowError>
     where
         Self: Sized,
     {
-        let (user_id, password_hash, created_at, updated_at) = mysql::from_row_opt::<(Vec<u8>, String, NaiveDateTime, NaiveDateTime)>(row)?;
+        let (user_id, password_hash, created_at, updated_at) = mysql::from_row_opt::<(String, String, NaiveDateTime, NaiveDateTime)>(row.clone())?;
         Ok(UserAuth {
-            user_id: Uuid::from_slice(&user_id).expect("Error converting user_id to Uuid"),
+            user_id: parse_uuid(&user_id, &row)?,
             password_hash,
             created_at,
             updated_at,
@@ -116,3 +109,128 @@ impl FromRow for UserAndUserAuth {
         })
     }
 }
+
+/// A user's OAuth tokens for a brokerage. Tokens are encrypted at rest; rows
+/// read with `FromRow` still hold ciphertext until `EncryptedBrokerConnections` decrypts them.
+#[derive(Clone)]
+pub struct BrokerConnection {
+    pub user_id: Uuid,
+    pub broker: String,
+    pub access_token: String,
+    pub refresh_token: String,
+    pub access_token_expires_at: NaiveDateTime,
+    pub refresh_token_expires_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl FromRow for BrokerConnection {
+    fn from_row(row: mysql::Row) -> Self
+    where
+        Self: Sized,
+    {
+        Self::from_row_opt(row).expect("Error converting row to BrokerConnection")
+    }
+
+    fn from_row_opt(row: mysql::Row) -> Result<Self, mysql::FromRowError>
+    where
+        Self: Sized,
+    {
+        let (user_id, broker, access_token, refresh_token, access_token_expires_at, refresh_token_expires_at, created_at, updated_at) =
+            mysql::from_row_opt::<(String, String, String, String, NaiveDateTime, NaiveDateTime, NaiveDateTime, NaiveDateTime)>(row.clone())?;
+        Ok(BrokerConnection {
+            user_id: parse_uuid(&user_id, &row)?,
+            broker,
+            access_token,
+            refresh_token,
+            access_token_expires_at,
+            refresh_token_expires_at,
+            created_at,
+            updated_at,
+        })
+    }
+}
+
+/// One leg of a filled order, as recorded in the user's trade journal. The fill fields
+/// come from TDA and are refreshed on every sync; the annotations are the user's own.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Trade {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub account_id: i64,
+    pub order_id: i64,
+    pub leg_id: i64,
+    pub symbol: String,
+    pub asset_type: String,
+    pub instruction: String,
+    pub position_effect: Option<String>,
+    pub quantity: f64,
+    /// Average fill price, weighted by quantity.
+    pub price: f64,
+    pub first_filled_at: NaiveDateTime,
+    pub last_filled_at: NaiveDateTime,
+    pub notes: String,
+    pub tags: Vec<String>,
+    pub setup: Option<String>,
+    /// Links to chart screenshots hosted elsewhere.
+    pub screenshots: Vec<String>,
+    pub executions: Vec<TradeExecution>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl FromRow for Trade {
+    fn from_row(row: mysql::Row) -> Self
+    where
+        Self: Sized,
+    {
+        Self::from_row_opt(row).expect("Error converting row to Trade")
+    }
+
+    /// Executions live in their own table and are left empty here.
+    fn from_row_opt(row: mysql::Row) -> Result<Self, mysql::FromRowError>
+    where
+        Self: Sized,
+    {
+        fn take<T: mysql::prelude::FromValue>(row: &mut mysql::Row, column: &str) -> Option<T> {
+            row.take_opt(column).and_then(Result::ok)
+        }
+
+        let mut columns = row.clone();
+        let trade = (|| {
+            Some(Trade {
+                id: Uuid::parse_str(&take::<String>(&mut columns, "id")?).ok()?,
+                user_id: Uuid::parse_str(&take::<String>(&mut columns, "user_id")?).ok()?,
+                account_id: take(&mut columns, "account_id")?,
+                order_id: take(&mut columns, "order_id")?,
+                leg_id: take(&mut columns, "leg_id")?,
+                symbol: take(&mut columns, "symbol")?,
+                asset_type: take(&mut columns, "asset_type")?,
+                instruction: take(&mut columns, "instruction")?,
+                position_effect: take(&mut columns, "position_effect")?,
+                quantity: take(&mut columns, "quantity")?,
+                price: take(&mut columns, "price")?,
+                first_filled_at: take(&mut columns, "first_filled_at")?,
+                last_filled_at: take(&mut columns, "last_filled_at")?,
+                notes: take(&mut columns, "notes")?,
+                tags: serde_json::from_str(&take::<String>(&mut columns, "tags")?).ok()?,
+                setup: take(&mut columns, "setup")?,
+                screenshots: serde_json::from_str(&take::<String>(&mut columns, "screenshots")?).ok()?,
+                executions: vec![],
+                created_at: take(&mut columns, "created_at")?,
+                updated_at: take(&mut columns, "updated_at")?,
+            })
+        })();
+        trade.ok_or(mysql::FromRowError(row))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct TradeExecution {
+    /// Position of the fill among the trade's fills, oldest first.
+    pub sequence: u32,
+    pub activity_id: Option<i64>,
+    pub quantity: f64,
+    pub price: f64,
+    pub filled_at: NaiveDateTime,
+}
@@ -0,0 +1,119 @@
+use super::{
+    error::DatabaseError,
+    models::{BrokerConnection, Trade, User},
+    CandleSeries, CreateUser, CreateUserAuth, DatabaseClient, GetUserAuthByEmail, TradeAnnotation, TradeFilter, UpsertBrokerConnection, UpsertTrade,
+};
+use crate::{tda_client::price_history::Candle, utils::crypto::Cipher};
+use async_trait::async_trait;
+use std::env;
+use uuid::Uuid;
+
+#[async_trait]
+pub trait UserRepository: Send + Sync {
+    async fn create_user_and_user_auth(&self, user: CreateUser, user_auth: CreateUserAuth) -> Result<(), DatabaseError>;
+    async fn get_user_by_email(&self, email: &str) -> Result<Option<User>, DatabaseError>;
+    async fn get_user_auth_by_email(&self, email: &str) -> Result<Option<GetUserAuthByEmail>, DatabaseError>;
+}
+
+#[async_trait]
+pub trait BrokerConnectionRepository: Send + Sync {
+    async fn upsert_broker_connection(&self, connection: UpsertBrokerConnection) -> Result<(), DatabaseError>;
+    async fn get_broker_connection(&self, user_id: Uuid, broker: &str) -> Result<Option<BrokerConnection>, DatabaseError>;
+}
+
+#[async_trait]
+pub trait CandleRepository: Send + Sync {
+    async fn get_cached_candles(&self, series: &CandleSeries, start: i64, end: i64) -> Result<Option<Vec<Candle>>, DatabaseError>;
+    async fn cache_candles(&self, series: &CandleSeries, start: i64, end: i64, candles: Vec<Candle>) -> Result<(), DatabaseError>;
+}
+
+#[async_trait]
+pub trait TradeRepository: Send + Sync {
+    async fn upsert_trades(&self, trades: Vec<UpsertTrade>) -> Result<(), DatabaseError>;
+    async fn get_trades(&self, user_id: Uuid, filter: &TradeFilter) -> Result<Vec<Trade>, DatabaseError>;
+    async fn get_trade(&self, user_id: Uuid, trade_id: Uuid) -> Result<Option<Trade>, DatabaseError>;
+    async fn annotate_trade(&self, user_id: Uuid, trade_id: Uuid, annotation: TradeAnnotation) -> Result<Option<Trade>, DatabaseError>;
+}
+
+#[async_trait]
+impl UserRepository for DatabaseClient {
+    async fn create_user_and_user_auth(&self, user: CreateUser, user_auth: CreateUserAuth) -> Result<(), DatabaseError> {
+        DatabaseClient::create_user_and_user_auth(self, user, user_auth).await
+    }
+
+    async fn get_user_by_email(&self, email: &str) -> Result<Option<User>, DatabaseError> {
+        DatabaseClient::get_user_by_email(self, email).await
+    }
+
+    async fn get_user_auth_by_email(&self, email: &str) -> Result<Option<GetUserAuthByEmail>, DatabaseError> {
+        DatabaseClient::get_user_auth_by_email(self, email).await
+    }
+}
+
+/// Broker connections in MySQL with their tokens encrypted at rest. Only this repository
+/// needs `BROKER_TOKEN_ENCRYPTION_KEY`, so tools like `migrate` run without it.
+pub struct EncryptedBrokerConnections {
+    database: DatabaseClient,
+    cipher: Cipher,
+}
+
+impl EncryptedBrokerConnections {
+    pub fn new(database: DatabaseClient, cipher: Cipher) -> Self {
+        Self { database, cipher }
+    }
+
+    pub fn from_env(database: DatabaseClient) -> Self {
+        let encryption_key = env::var("BROKER_TOKEN_ENCRYPTION_KEY").expect("BROKER_TOKEN_ENCRYPTION_KEY not found");
+        let cipher = Cipher::new(&encryption_key).expect("BROKER_TOKEN_ENCRYPTION_KEY is invalid");
+        Self::new(database, cipher)
+    }
+}
+
+#[async_trait]
+impl BrokerConnectionRepository for EncryptedBrokerConnections {
+    async fn upsert_broker_connection(&self, mut connection: UpsertBrokerConnection) -> Result<(), DatabaseError> {
+        connection.access_token = self.cipher.encrypt(&connection.access_token);
+        connection.refresh_token = self.cipher.encrypt(&connection.refresh_token);
+        self.database.upsert_broker_connection(connection).await
+    }
+
+    async fn get_broker_connection(&self, user_id: Uuid, broker: &str) -> Result<Option<BrokerConnection>, DatabaseError> {
+        let mut connection = match self.database.get_broker_connection(user_id, broker).await? {
+            Some(connection) => connection,
+            None => return Ok(None),
+        };
+        connection.access_token = self.cipher.decrypt(&connection.access_token)?;
+        connection.refresh_token = self.cipher.decrypt(&connection.refresh_token)?;
+        Ok(Some(connection))
+    }
+}
+
+#[async_trait]
+impl CandleRepository for DatabaseClient {
+    async fn get_cached_candles(&self, series: &CandleSeries, start: i64, end: i64) -> Result<Option<Vec<Candle>>, DatabaseError> {
+        DatabaseClient::get_cached_candles(self, series, start, end).await
+    }
+
+    async fn cache_candles(&self, series: &CandleSeries, start: i64, end: i64, candles: Vec<Candle>) -> Result<(), DatabaseError> {
+        DatabaseClient::cache_candles(self, series, start, end, candles).await
+    }
+}
+
+#[async_trait]
+impl TradeRepository for DatabaseClient {
+    async fn upsert_trades(&self, trades: Vec<UpsertTrade>) -> Result<(), DatabaseError> {
+        DatabaseClient::upsert_trades(self, trades).await
+    }
+
+    async fn get_trades(&self, user_id: Uuid, filter: &TradeFilter) -> Result<Vec<Trade>, DatabaseError> {
+        DatabaseClient::get_trades(self, user_id, filter).await
+    }
+
+    async fn get_trade(&self, user_id: Uuid, trade_id: Uuid) -> Result<Option<Trade>, DatabaseError> {
+        DatabaseClient::get_trade(self, user_id, trade_id).await
+    }
+
+    async fn annotate_trade(&self, user_id: Uuid, trade_id: Uuid, annotation: TradeAnnotation) -> Result<Option<Trade>, DatabaseError> {
+        DatabaseClient::annotate_trade(self, user_id, trade_id, annotation).await
+    }
+}
@@ -0,0 +1,74 @@
+use crate::{database_client::error::DatabaseError, tda_client::error::TdaError};
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use log::error;
+use serde::Serialize;
+
+/// Body of every error response from our API, `{"error": "..."}`.
+#[derive(Debug, Serialize)]
+pub struct ErrorBody {
+    pub error: String,
+}
+
+/// Error returned by handlers that validate their input or read our own data, not only TDA's.
+#[derive(Debug)]
+pub enum ApiError {
+    /// The request itself is invalid; TDA was never called.
+    BadRequest(String),
+    /// The requested record does not exist or belongs to another user.
+    NotFound(String),
+    /// The TDA call failed.
+    Tda(TdaError),
+    /// Our database failed.
+    Storage(DatabaseError),
+}
+
+impl From<TdaError> for ApiError {
+    fn from(e: TdaError) -> Self {
+        ApiError::Tda(e)
+    }
+}
+
+impl From<DatabaseError> for ApiError {
+    fn from(e: DatabaseError) -> Self {
+        ApiError::Storage(e)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        match self {
+            ApiError::BadRequest(error) => (StatusCode::BAD_REQUEST, Json(ErrorBody { error })).into_response(),
+            ApiError::NotFound(error) => (StatusCode::NOT_FOUND, Json(ErrorBody { error })).into_response(),
+            ApiError::Tda(e) => e.into_response(),
+            ApiError::Storage(e) => {
+                error!("Handler storage error: {}", e);
+                let status = match e {
+                    DatabaseError::PoolTimeout => StatusCode::SERVICE_UNAVAILABLE,
+                    _ => StatusCode::INTERNAL_SERVER_ERROR,
+                };
+                (status, Json(ErrorBody { error: "storage error".to_string() })).into_response()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ApiError;
+    use crate::{database_client::error::DatabaseError, tda_client::error::TdaError};
+    use axum::{http::StatusCode, response::IntoResponse};
+
+    #[test]
+    fn test_into_response() {
+        assert_eq!(ApiError::BadRequest("symbols is required".to_string()).into_response().status(), StatusCode::BAD_REQUEST);
+        assert_eq!(ApiError::NotFound("trade not found".to_string()).into_response().status(), StatusCode::NOT_FOUND);
+        assert_eq!(ApiError::from(DatabaseError::PoolTimeout).into_response().status(), StatusCode::SERVICE_UNAVAILABLE);
+        let response = ApiError::from(TdaError::RateLimited(Some(30))).into_response();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()["retry-after"], "30");
+    }
+}
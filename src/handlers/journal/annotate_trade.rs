@@ -0,0 +1,70 @@
+use crate::{
+    database_client::{models::Trade, TradeAnnotation},
+    handlers::error::ApiError,
+    middleware::jwt::AuthUser,
+    AppState,
+};
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use url::Url;
+use uuid::Uuid;
+
+/// Updates the notes, tags, setup or screenshots of a journal trade.
+pub async fn annotate_trade(user: AuthUser, State(state): State<AppState>, Path(trade_id): Path<Uuid>, Json(annotation): Json<TradeAnnotation>) -> Result<Json<Trade>, ApiError> {
+    // Screenshots are only linked, never uploaded, so each has to be a web URL.
+    for screenshot in annotation.screenshots.iter().flatten() {
+        if !Url::parse(screenshot).is_ok_and(|url| matches!(url.scheme(), "http" | "https")) {
+            return Err(ApiError::BadRequest(format!("screenshot {} is not an http(s) URL", screenshot)));
+        }
+    }
+    match state.trades.annotate_trade(user.user_id, trade_id, annotation).await? {
+        Some(trade) => Ok(Json(trade)),
+        None => Err(ApiError::NotFound("trade not found".to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tda_client::mock::{authed_server, MockTda};
+    use axum::http::StatusCode;
+    use serde_json::{json, Value};
+    use uuid::Uuid;
+
+    #[tokio::test]
+    async fn test_annotate_trade() {
+        let tda = MockTda::start();
+        let (server, cookie) = authed_server(&tda).await;
+        server.post("/api/journal/sync").add_cookie(cookie.clone()).await;
+        let trades = server.get("/api/journal/trades?symbol=MSFT").add_cookie(cookie.clone()).await.json::<Vec<Value>>();
+        let path = format!("/api/journal/trades/{}", trades[0]["id"].as_str().unwrap());
+
+        let res = server
+            .patch(&path)
+            .add_cookie(cookie.clone())
+            .json(&json!({"screenshots": ["javascript:alert(1)"]}))
+            .expect_failure()
+            .await;
+        assert_eq!(res.status_code(), StatusCode::BAD_REQUEST);
+
+        let annotation = json!({
+            "notes": "Bought the breakout",
+            "tags": ["breakout", " breakout ", ""],
+            "setup": "flag",
+            "screenshots": ["https://example.com/msft.png"],
+        });
+        let trade = server.patch(&path).add_cookie(cookie.clone()).json(&annotation).await.json::<Value>();
+        assert_eq!(trade["tags"], json!(["breakout"]));
+        server.patch(&path).add_cookie(cookie.clone()).json(&json!({"setup": ""})).await;
+
+        let trade = server.get(&path).add_cookie(cookie.clone()).await.json::<Value>();
+        assert_eq!(trade["notes"], "Bought the breakout");
+        assert_eq!(trade["setup"], Value::Null);
+        assert_eq!(trade["screenshots"], json!(["https://example.com/msft.png"]));
+        assert_eq!(server.get("/api/journal/trades?tag=breakout").add_cookie(cookie.clone()).await.json::<Vec<Value>>().len(), 1);
+
+        let res = server.get(&format!("/api/journal/trades/{}", Uuid::new_v4())).add_cookie(cookie.clone()).expect_failure().await;
+        assert_eq!(res.status_code(), StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,13 @@
+use crate::{database_client::models::Trade, handlers::error::ApiError, middleware::jwt::AuthUser, AppState};
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use uuid::Uuid;
+
+pub async fn get_trade(user: AuthUser, State(state): State<AppState>, Path(trade_id): Path<Uuid>) -> Result<Json<Trade>, ApiError> {
+    match state.trades.get_trade(user.user_id, trade_id).await? {
+        Some(trade) => Ok(Json(trade)),
+        None => Err(ApiError::NotFound("trade not found".to_string())),
+    }
+}
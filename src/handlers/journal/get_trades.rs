@@ -0,0 +1,17 @@
+use crate::{
+    database_client::{models::Trade, TradeFilter},
+    handlers::error::ApiError,
+    middleware::jwt::AuthUser,
+    AppState,
+};
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+
+/// The user's journal, most recent trade first.
+pub async fn get_trades(user: AuthUser, State(state): State<AppState>, Query(mut filter): Query<TradeFilter>) -> Result<Json<Vec<Trade>>, ApiError> {
+    filter.symbol = filter.symbol.map(|symbol| symbol.to_uppercase());
+    let trades = state.trades.get_trades(user.user_id, &filter).await?;
+    Ok(Json(trades))
+}
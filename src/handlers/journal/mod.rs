@@ -0,0 +1,8 @@
+pub mod annotate_trade;
+pub use annotate_trade::annotate_trade;
+pub mod get_trade;
+pub use get_trade::get_trade;
+pub mod get_trades;
+pub use get_trades::get_trades;
+pub mod sync_journal;
+pub use sync_journal::sync_journal;
@@ -0,0 +1,61 @@
+use crate::{
+    handlers::error::ApiError,
+    journal::trades_from_orders,
+    middleware::jwt::AuthUser,
+    tda_client::accounts::{GetOrdersQuery, TDAmeritradeClientAccounts},
+    AppState,
+};
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct SyncJournalResponse {
+    /// Filled legs found, whether they were new to the journal or not.
+    trades: usize,
+}
+
+/// Records the fills of the user's TDA orders in their journal. The query selects the
+/// orders as for `/orders`; syncing a range again only refreshes the trades in it.
+pub async fn sync_journal(user: AuthUser, State(state): State<AppState>, Query(query): Query<GetOrdersQuery>) -> Result<Json<SyncJournalResponse>, ApiError> {
+    query.validate().map_err(ApiError::BadRequest)?;
+    let (tda_client, query) = (&state.tda_client, &query);
+    let orders = state
+        .token_manager
+        .with_access_token(user.user_id, |token| async move { tda_client.get_orders_for_all_accounts(&token, query).await })
+        .await?;
+    let trades = trades_from_orders(user.user_id, &orders);
+    let synced = trades.len();
+    state.trades.upsert_trades(trades).await?;
+    Ok(Json(SyncJournalResponse { trades: synced }))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tda_client::mock::{authed_server, MockTda};
+    use serde_json::Value;
+
+    #[tokio::test]
+    async fn test_sync_journal_is_idempotent() {
+        let tda = MockTda::start();
+        let (server, cookie) = authed_server(&tda).await;
+
+        for _ in 0..2 {
+            let res = server.post("/api/journal/sync").add_cookie(cookie.clone()).await;
+            assert_eq!(res.json::<Value>()["trades"], 3);
+        }
+
+        let res = server.get("/api/journal/trades").add_cookie(cookie.clone()).await;
+        let trades = res.json::<Vec<Value>>();
+        let order_ids: Vec<&Value> = trades.iter().map(|trade| &trade["order_id"]).collect();
+        assert_eq!(order_ids, vec![1008, 1011, 1001]);
+        assert_eq!(trades[0]["executions"].as_array().unwrap().len(), 1);
+
+        let res = server.get("/api/journal/trades?symbol=aapl&account_id=123456789&to=2023-03-01").add_cookie(cookie).await;
+        let trades = res.json::<Vec<Value>>();
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0]["price"], 142.5);
+    }
+}
@@ -1,3 +1,5 @@
+pub mod error;
+pub mod journal;
 pub mod root;
 pub use root::root;
 pub mod providers;
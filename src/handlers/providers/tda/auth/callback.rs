@@ -1,39 +1,83 @@
 use crate::{
+    middleware::jwt::{validate_token, TokenType},
     tda_client::auth::TDAmeritradeClientAuth,
-    utils::{
-        cookie::{create_access_token, create_refresh_token},
-        get_base_url,
-    },
+    utils::get_base_url,
     AppState,
 };
 use axum::{
     extract::{Query, State},
     response::{IntoResponse, Redirect},
 };
-use axum_extra::extract::cookie::CookieJar;
 use log::error;
 
 #[derive(serde::Deserialize)]
 pub struct AuthCallbackTdaQuery {
     code: String,
+    state: String,
 }
 
-pub async fn tda(jar: CookieJar, State(state): State<AppState>, Query(query): Query<AuthCallbackTdaQuery>) -> impl IntoResponse {
+pub async fn tda(State(state): State<AppState>, Query(query): Query<AuthCallbackTdaQuery>) -> impl IntoResponse {
     let code = &query.code;
     let base_url = get_base_url();
+    let claims = match validate_token(&query.state, &state.env.jwt_oauth_state_secret, TokenType::OauthState) {
+        Ok(claims) => claims,
+        Err(e) => {
+            error!("auth_callback_tda state error: {}", e);
+            return Redirect::temporary(base_url.as_str());
+        }
+    };
     let token_response = match state.tda_client.exchange_authorization_code_for_token(code).await {
         Ok(data) => data,
         Err(e) => {
             error!("auth_callback_tda error: {}", e);
-            return (jar, Redirect::temporary(base_url.as_str()));
+            return Redirect::temporary(base_url.as_str());
         }
     };
-    let access_token_str = token_response.access_token.unwrap_or_default();
-    let refresh_token_str = token_response.refresh_token.unwrap_or_default();
-    let mut access_token = create_access_token(access_token_str);
-    access_token.set_name("access_token_tda");
-    let mut refresh_token = create_refresh_token(refresh_token_str);
-    refresh_token.set_name("refresh_token_tda");
-    let jar = jar.add(access_token).add(refresh_token);
-    (jar, Redirect::temporary(base_url.as_str()))
+    if token_response.access_token.is_none() || token_response.refresh_token.is_none() {
+        error!("auth_callback_tda error: token response without access and refresh tokens");
+        return Redirect::temporary(base_url.as_str());
+    }
+    if let Err(e) = state.token_manager.store(claims.sub, &token_response).await {
+        error!("auth_callback_tda store error: {}", e);
+    }
+    Redirect::temporary(base_url.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        middleware::jwt::{create_access_token, create_oauth_state_token},
+        router::Router,
+        tda_client::mock::{MockTda, AUTHORIZATION_CODE},
+        AppState,
+    };
+    use axum::http::StatusCode;
+    use axum_test::TestServer;
+    use cookie::Cookie;
+    use uuid::Uuid;
+
+    #[tokio::test]
+    async fn test_callback_stores_only_complete_tokens_for_valid_state() {
+        std::env::set_var("TRADETRACKER_API_BASE_URL", "http://localhost:3000");
+        let tda = MockTda::start();
+        let state = AppState::in_memory(tda.client());
+        let user_id = Uuid::new_v4();
+        let access_token = create_access_token(user_id, &state.env.jwt_access_token_secret);
+        let oauth_state = create_oauth_state_token(user_id, &state.env.jwt_oauth_state_secret);
+        let forged_state = create_oauth_state_token(user_id, &state.env.jwt_access_token_secret);
+        let server = TestServer::new(Router::new(state).get_router().into_make_service()).unwrap();
+        let callback = format!("/api/auth/callback/tda?code={}&state={}", AUTHORIZATION_CODE, oauth_state);
+
+        server.get(&format!("/api/auth/callback/tda?code={}&state={}", AUTHORIZATION_CODE, forged_state)).expect_failure().await;
+        assert!(tda.requests().is_empty());
+
+        tda.respond_next(StatusCode::OK, r#"{"token_type":"Bearer","expires_in":1800}"#);
+        server.get(&callback).expect_failure().await;
+        let res = server.get("/api/get_accounts").add_cookie(Cookie::new("access_token", access_token.clone())).expect_failure().await;
+        assert_eq!(res.status_code(), StatusCode::UNAUTHORIZED);
+
+        server.get(&callback).expect_failure().await;
+        let res = server.get("/api/get_accounts").add_cookie(Cookie::new("access_token", access_token)).await;
+        assert_eq!(res.status_code(), StatusCode::OK);
+    }
 }
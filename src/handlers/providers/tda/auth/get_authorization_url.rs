@@ -1,26 +1,42 @@
-use crate::{tda_client::auth::TDAmeritradeClientAuth, AppState};
+use crate::{
+    middleware::jwt::{create_oauth_state_token, AuthUser},
+    tda_client::auth::TDAmeritradeClientAuth,
+    AppState,
+};
 use axum::{extract::State, http::StatusCode, Json};
+use url::form_urlencoded;
 
 #[derive(serde::Serialize)]
 pub struct GetAuthorizationUrlResponse {
     authorization_url: String,
 }
 
-pub async fn get_authorization_url(State(state): State<AppState>) -> (StatusCode, Json<GetAuthorizationUrlResponse>) {
-    let authorization_url = state.tda_client.get_authorization_url();
+pub async fn get_authorization_url(user: AuthUser, State(state): State<AppState>) -> (StatusCode, Json<GetAuthorizationUrlResponse>) {
+    let oauth_state = create_oauth_state_token(user.user_id, &state.env.jwt_oauth_state_secret);
+    let oauth_state = form_urlencoded::byte_serialize(oauth_state.as_bytes()).collect::<String>();
+    let authorization_url = format!("{}&state={}", state.tda_client.get_authorization_url(), oauth_state);
     (StatusCode::OK, Json(GetAuthorizationUrlResponse { authorization_url }))
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::router::Router;
+    use crate::{middleware::jwt::create_access_token, router::Router, tda_client::mock::MockTda, AppState};
     use axum_test::TestServer;
+    use cookie::Cookie;
+    use serde_json::Value;
+    use uuid::Uuid;
 
     #[tokio::test]
     async fn test_get_authorization_url() {
-        let router = Router::default().get_router();
+        let tda = MockTda::start();
+        let state = AppState::in_memory(tda.client());
+        let access_token = create_access_token(Uuid::new_v4(), &state.env.jwt_access_token_secret);
+        let router = Router::new(state).get_router();
         let server = TestServer::new(router.into_make_service()).unwrap();
-        let res = server.get("/api/get_authorization_url").await;
+        let res = server.get("/api/auth/providers/tda").add_cookie(Cookie::new("access_token", access_token)).await;
         assert_eq!(res.status_code(), 200);
+        let authorization_url = res.json::<Value>()["authorization_url"].as_str().unwrap().to_string();
+        assert!(authorization_url.starts_with(&tda.config().auth_url));
+        assert!(authorization_url.contains("&state="));
     }
 }
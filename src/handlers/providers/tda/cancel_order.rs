@@ -0,0 +1,23 @@
+use crate::{
+    middleware::jwt::AuthUser,
+    tda_client::{error::TdaError, orders::TDAmeritradeClientOrders},
+    AppState,
+};
+use axum::extract::{Path, State};
+use hyper::StatusCode;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct CancelOrderPath {
+    account_id: String,
+    order_id: String,
+}
+
+pub async fn cancel_order(user: AuthUser, State(state): State<AppState>, Path(path): Path<CancelOrderPath>) -> Result<StatusCode, TdaError> {
+    let (tda_client, path) = (&state.tda_client, &path);
+    state
+        .token_manager
+        .with_access_token(user.user_id, |token| async move { tda_client.cancel_order(&token, &path.account_id, &path.order_id).await })
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,31 @@
+use crate::{
+    middleware::jwt::AuthUser,
+    tda_client::{error::TdaError, orders::OrderRequest, saved_orders::TDAmeritradeClientSavedOrders},
+    AppState,
+};
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    Json,
+};
+use hyper::StatusCode;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize)]
+pub struct CreateSavedOrderPath {
+    account_id: String,
+}
+
+#[derive(Serialize)]
+pub struct CreateSavedOrderResponse {
+    pub saved_order_id: Option<i64>,
+}
+
+pub async fn create_saved_order(user: AuthUser, State(state): State<AppState>, Path(path): Path<CreateSavedOrderPath>, Json(order): Json<OrderRequest>) -> Result<impl IntoResponse, TdaError> {
+    let (tda_client, path, order) = (&state.tda_client, &path, &order);
+    let saved_order_id = state
+        .token_manager
+        .with_access_token(user.user_id, |token| async move { tda_client.create_saved_order(&token, &path.account_id, order).await })
+        .await?;
+    Ok((StatusCode::CREATED, Json(CreateSavedOrderResponse { saved_order_id })))
+}
@@ -0,0 +1,34 @@
+use crate::{
+    middleware::jwt::AuthUser,
+    tda_client::{
+        error::TdaError,
+        watchlists::{TDAmeritradeClientWatchlists, WatchlistRequest},
+    },
+    AppState,
+};
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    Json,
+};
+use hyper::StatusCode;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize)]
+pub struct CreateWatchlistPath {
+    account_id: String,
+}
+
+#[derive(Serialize)]
+pub struct CreateWatchlistResponse {
+    pub watchlist_id: Option<String>,
+}
+
+pub async fn create_watchlist(user: AuthUser, State(state): State<AppState>, Path(path): Path<CreateWatchlistPath>, Json(watchlist): Json<WatchlistRequest>) -> Result<impl IntoResponse, TdaError> {
+    let (tda_client, path, watchlist) = (&state.tda_client, &path, &watchlist);
+    let watchlist_id = state
+        .token_manager
+        .with_access_token(user.user_id, |token| async move { tda_client.create_watchlist(&token, &path.account_id, watchlist).await })
+        .await?;
+    Ok((StatusCode::CREATED, Json(CreateWatchlistResponse { watchlist_id })))
+}
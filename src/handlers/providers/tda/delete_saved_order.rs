@@ -0,0 +1,23 @@
+use crate::{
+    middleware::jwt::AuthUser,
+    tda_client::{error::TdaError, saved_orders::TDAmeritradeClientSavedOrders},
+    AppState,
+};
+use axum::extract::{Path, State};
+use hyper::StatusCode;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct DeleteSavedOrderPath {
+    account_id: String,
+    saved_order_id: String,
+}
+
+pub async fn delete_saved_order(user: AuthUser, State(state): State<AppState>, Path(path): Path<DeleteSavedOrderPath>) -> Result<StatusCode, TdaError> {
+    let (tda_client, path) = (&state.tda_client, &path);
+    state
+        .token_manager
+        .with_access_token(user.user_id, |token| async move { tda_client.delete_saved_order(&token, &path.account_id, &path.saved_order_id).await })
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
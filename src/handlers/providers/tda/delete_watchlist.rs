@@ -0,0 +1,23 @@
+use crate::{
+    middleware::jwt::AuthUser,
+    tda_client::{error::TdaError, watchlists::TDAmeritradeClientWatchlists},
+    AppState,
+};
+use axum::extract::{Path, State};
+use hyper::StatusCode;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct DeleteWatchlistPath {
+    account_id: String,
+    watchlist_id: String,
+}
+
+pub async fn delete_watchlist(user: AuthUser, State(state): State<AppState>, Path(path): Path<DeleteWatchlistPath>) -> Result<StatusCode, TdaError> {
+    let (tda_client, path) = (&state.tda_client, &path);
+    state
+        .token_manager
+        .with_access_token(user.user_id, |token| async move { tda_client.delete_watchlist(&token, &path.account_id, &path.watchlist_id).await })
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,26 @@
+use crate::{
+    middleware::jwt::AuthUser,
+    tda_client::{
+        accounts::{GetAccountsResponse, TDAmeritradeClientAccounts},
+        error::TdaError,
+    },
+    AppState,
+};
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+
+#[derive(serde::Deserialize)]
+pub struct GetAccountPath {
+    account_id: String,
+}
+
+pub async fn get_account(user: AuthUser, State(state): State<AppState>, Path(path): Path<GetAccountPath>) -> Result<Json<GetAccountsResponse>, TdaError> {
+    let (tda_client, path) = (&state.tda_client, &path);
+    let account = state
+        .token_manager
+        .with_access_token(user.user_id, |token| async move { tda_client.get_account(&token, &path.account_id).await })
+        .await?;
+    Ok(Json(account))
+}
@@ -0,0 +1,17 @@
+use crate::{
+    middleware::jwt::AuthUser,
+    tda_client::{error::TdaError, user_principals::TDAmeritradeClientUserPrincipals},
+    AppState,
+};
+use axum::{extract::State, Json};
+use std::collections::HashMap;
+
+/// Account id to the nickname set in TDA, to join against `/get_accounts`.
+pub async fn get_account_display_names(user: AuthUser, State(state): State<AppState>) -> Result<Json<HashMap<String, String>>, TdaError> {
+    let tda_client = &state.tda_client;
+    let principals = state
+        .token_manager
+        .with_access_token(user.user_id, |token| async move { tda_client.get_user_principals(&token, &[]).await })
+        .await?;
+    Ok(Json(principals.account_display_names()))
+}
@@ -0,0 +1,26 @@
+use crate::{
+    middleware::jwt::AuthUser,
+    tda_client::{
+        error::TdaError,
+        watchlists::{TDAmeritradeClientWatchlists, Watchlist},
+    },
+    AppState,
+};
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+
+#[derive(serde::Deserialize)]
+pub struct GetAccountWatchlistsPath {
+    account_id: String,
+}
+
+pub async fn get_account_watchlists(user: AuthUser, State(state): State<AppState>, Path(path): Path<GetAccountWatchlistsPath>) -> Result<Json<Vec<Watchlist>>, TdaError> {
+    let (tda_client, path) = (&state.tda_client, &path);
+    let watchlists = state
+        .token_manager
+        .with_access_token(user.user_id, |token| async move { tda_client.get_watchlists(&token, &path.account_id).await })
+        .await?;
+    Ok(Json(watchlists))
+}
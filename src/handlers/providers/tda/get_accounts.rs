@@ -1,14 +1,38 @@
-use crate::{tda_client::accounts::TDAmeritradeClientAccounts, AppState};
-use axum::{extract::State, response::IntoResponse, Json};
-use axum_extra::extract::CookieJar;
-use hyper::StatusCode;
-
-pub async fn get_accounts(jar: CookieJar, State(state): State<AppState>) -> impl IntoResponse {
-    let access_token_cookie = jar.get("access_token_tda");
-    let token = match access_token_cookie {
-        Some(cookie) => cookie.value(),
-        None => return (StatusCode::UNAUTHORIZED, Json(vec![])),
-    };
-    let token_response = state.tda_client.get_accounts(token).await;
-    (StatusCode::OK, Json(token_response))
+use crate::{
+    middleware::jwt::AuthUser,
+    tda_client::{
+        accounts::{GetAccountsResponse, TDAmeritradeClientAccounts},
+        error::TdaError,
+    },
+    AppState,
+};
+use axum::{extract::State, Json};
+
+pub async fn get_accounts(user: AuthUser, State(state): State<AppState>) -> Result<Json<Vec<GetAccountsResponse>>, TdaError> {
+    let tda_client = &state.tda_client;
+    let accounts = state
+        .token_manager
+        .with_access_token(user.user_id, |token| async move { tda_client.get_accounts(&token).await })
+        .await?;
+    Ok(Json(accounts))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tda_client::mock::{authed_server, MockTda};
+    use serde_json::Value;
+
+    #[tokio::test]
+    async fn test_get_accounts_refreshes_expired_token() {
+        let tda = MockTda::start();
+        let (server, cookie) = authed_server(&tda).await;
+
+        let res = server.get("/api/get_accounts").add_cookie(cookie.clone()).await;
+        assert_eq!(res.json::<Vec<Value>>().len(), 2);
+
+        tda.expire_access_tokens();
+        let res = server.get("/api/get_accounts").add_cookie(cookie).await;
+        assert_eq!(res.json::<Vec<Value>>().len(), 2);
+        assert_eq!(tda.requests(), vec!["GET /v1/accounts", "GET /v1/accounts", "POST /v1/oauth2/token", "GET /v1/accounts"]);
+    }
 }
@@ -0,0 +1,21 @@
+use crate::{
+    handlers::error::ApiError,
+    middleware::jwt::AuthUser,
+    tda_client::accounts::{GetOrdersQuery, Order, TDAmeritradeClientAccounts},
+    AppState,
+};
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+
+/// Orders across every account linked to the user's TDA login.
+pub async fn get_all_orders(user: AuthUser, State(state): State<AppState>, Query(query): Query<GetOrdersQuery>) -> Result<Json<Vec<Order>>, ApiError> {
+    query.validate().map_err(ApiError::BadRequest)?;
+    let (tda_client, query) = (&state.tda_client, &query);
+    let orders = state
+        .token_manager
+        .with_access_token(user.user_id, |token| async move { tda_client.get_orders_for_all_accounts(&token, query).await })
+        .await?;
+    Ok(Json(orders))
+}
@@ -0,0 +1,26 @@
+use crate::{
+    middleware::jwt::AuthUser,
+    tda_client::{
+        error::TdaError,
+        instruments::{InstrumentInfo, TDAmeritradeClientInstruments},
+    },
+    AppState,
+};
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+
+#[derive(serde::Deserialize)]
+pub struct GetInstrumentPath {
+    cusip: String,
+}
+
+pub async fn get_instrument(user: AuthUser, State(state): State<AppState>, Path(path): Path<GetInstrumentPath>) -> Result<Json<InstrumentInfo>, TdaError> {
+    let (tda_client, path) = (&state.tda_client, &path);
+    let instrument = state
+        .token_manager
+        .with_access_token(user.user_id, |token| async move { tda_client.get_instrument(&token, &path.cusip).await })
+        .await?;
+    Ok(Json(instrument))
+}
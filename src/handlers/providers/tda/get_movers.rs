@@ -0,0 +1,52 @@
+use crate::{
+    middleware::jwt::AuthUser,
+    tda_client::{
+        error::TdaError,
+        movers::{Mover, MoversIndex, MoversQuery, TDAmeritradeClientMovers},
+    },
+    AppState,
+};
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+
+/// `index` is one of `$COMPX`, `$DJI` or `$SPX.X`. Served from a cache shared by all users;
+/// only a cache miss needs the user's TDA token.
+pub async fn get_movers(user: AuthUser, State(state): State<AppState>, Path(index): Path<MoversIndex>, Query(query): Query<MoversQuery>) -> Result<Json<Vec<Mover>>, TdaError> {
+    let (tda_client, token_manager, query) = (&state.tda_client, &state.token_manager, &query);
+    let fetch = || token_manager.with_access_token(user.user_id, |token| async move { tda_client.get_movers(&token, index, query).await });
+    let movers = state.movers_cache.movers(index, query, fetch).await?;
+    Ok(Json(movers))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        middleware::jwt::create_access_token,
+        router::Router,
+        tda_client::mock::{authed_state, MockTda},
+    };
+    use axum::http::StatusCode;
+    use axum_test::TestServer;
+    use cookie::Cookie;
+    use uuid::Uuid;
+
+    #[tokio::test]
+    async fn test_cached_movers_need_no_tda_connection() {
+        let tda = MockTda::start();
+        let (state, connected) = authed_state(&tda).await;
+        // Signed in to our API, but never connected TDA.
+        let unconnected = create_access_token(Uuid::new_v4(), &state.env.jwt_access_token_secret);
+        let server = TestServer::new(Router::new(state).get_router().into_make_service()).unwrap();
+        let res = server.get("/api/movers/$SPX.X?direction=up").add_cookie(Cookie::new("access_token", connected)).await;
+        assert_eq!(res.status_code(), StatusCode::OK);
+
+        let res = server.get("/api/movers/$SPX.X?direction=up").add_cookie(Cookie::new("access_token", unconnected.clone())).await;
+        assert_eq!(res.status_code(), StatusCode::OK);
+        assert_eq!(tda.requests().len(), 1);
+
+        let res = server.get("/api/movers/$DJI?direction=up").add_cookie(Cookie::new("access_token", unconnected)).expect_failure().await;
+        assert_eq!(res.status_code(), StatusCode::UNAUTHORIZED);
+    }
+}
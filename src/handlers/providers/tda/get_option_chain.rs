@@ -0,0 +1,22 @@
+use crate::{
+    middleware::jwt::AuthUser,
+    tda_client::{
+        error::TdaError,
+        option_chains::{OptionChain, OptionChainQuery, TDAmeritradeClientOptionChains},
+    },
+    AppState,
+};
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+
+pub async fn get_option_chain(user: AuthUser, State(state): State<AppState>, Path(symbol): Path<String>, Query(query): Query<OptionChainQuery>) -> Result<Json<OptionChain>, TdaError> {
+    let symbol = symbol.to_uppercase();
+    let (tda_client, symbol, query) = (&state.tda_client, &symbol, &query);
+    let option_chain = state
+        .token_manager
+        .with_access_token(user.user_id, |token| async move { tda_client.get_option_chain(&token, symbol, query).await })
+        .await?;
+    Ok(Json(option_chain))
+}
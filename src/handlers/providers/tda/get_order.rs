@@ -0,0 +1,28 @@
+use crate::{
+    middleware::jwt::AuthUser,
+    tda_client::{
+        accounts::{Order, TDAmeritradeClientAccounts},
+        error::TdaError,
+    },
+    AppState,
+};
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct GetOrderPath {
+    account_id: String,
+    order_id: String,
+}
+
+pub async fn get_order(user: AuthUser, State(state): State<AppState>, Path(path): Path<GetOrderPath>) -> Result<Json<Order>, TdaError> {
+    let (tda_client, path) = (&state.tda_client, &path);
+    let order = state
+        .token_manager
+        .with_access_token(user.user_id, |token| async move { tda_client.get_order(&token, &path.account_id, &path.order_id).await })
+        .await?;
+    Ok(Json(order))
+}
@@ -1,13 +1,15 @@
 use axum::{
-    extract::{Path, State},
-    response::IntoResponse,
+    extract::{Path, Query, State},
     Json,
 };
-use axum_extra::extract::CookieJar;
 use axum_macros::debug_handler;
-use hyper::StatusCode;
 
-use crate::{tda_client::accounts::TDAmeritradeClientAccounts, AppState};
+use crate::{
+    handlers::error::ApiError,
+    middleware::jwt::AuthUser,
+    tda_client::accounts::{GetOrdersQuery, Order, TDAmeritradeClientAccounts},
+    AppState,
+};
 
 #[derive(serde::Deserialize)]
 pub struct GetOrdersPath {
@@ -15,12 +17,34 @@ pub struct GetOrdersPath {
 }
 
 #[debug_handler]
-pub async fn get_orders(jar: CookieJar, State(state): State<AppState>, Path(path): Path<GetOrdersPath>) -> impl IntoResponse {
-    let access_token_cookie = jar.get("access_token_tda");
-    let token = match access_token_cookie {
-        Some(cookie) => cookie.value(),
-        None => return (StatusCode::UNAUTHORIZED, Json(vec![])),
-    };
-    let token_response = state.tda_client.get_orders(&token, &path.account_id).await;
-    (StatusCode::OK, Json(token_response))
+pub async fn get_orders(user: AuthUser, State(state): State<AppState>, Path(path): Path<GetOrdersPath>, Query(query): Query<GetOrdersQuery>) -> Result<Json<Vec<Order>>, ApiError> {
+    query.validate().map_err(ApiError::BadRequest)?;
+    let (tda_client, path, query) = (&state.tda_client, &path, &query);
+    let orders = state
+        .token_manager
+        .with_access_token(user.user_id, |token| async move { tda_client.get_orders(&token, &path.account_id, query).await })
+        .await?;
+    Ok(Json(orders))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tda_client::mock::{authed_server, MockTda};
+    use axum::http::StatusCode;
+    use serde_json::Value;
+
+    #[tokio::test]
+    async fn test_get_orders_requires_both_range_ends() {
+        let tda = MockTda::start();
+        let (server, cookie) = authed_server(&tda).await;
+        for path in ["/api/123456789/get_orders?fromEnteredTime=2023-02-01", "/api/orders?toEnteredTime=2023-03-01"] {
+            let res = server.get(path).add_cookie(cookie.clone()).expect_failure().await;
+            assert_eq!(res.status_code(), StatusCode::BAD_REQUEST);
+            assert_eq!(res.json::<Value>()["error"], "fromEnteredTime and toEnteredTime must be given together");
+        }
+        assert!(tda.requests().is_empty());
+
+        let res = server.get("/api/123456789/get_orders?fromEnteredTime=2023-02-01&toEnteredTime=2023-03-01").add_cookie(cookie).await;
+        assert_eq!(res.status_code(), StatusCode::OK);
+    }
 }
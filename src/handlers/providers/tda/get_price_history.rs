@@ -0,0 +1,22 @@
+use crate::{
+    middleware::jwt::AuthUser,
+    tda_client::{
+        error::TdaError,
+        price_history::{PriceHistory, PriceHistoryQuery},
+    },
+    AppState,
+};
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+
+pub async fn get_price_history(user: AuthUser, State(state): State<AppState>, Path(symbol): Path<String>, Query(query): Query<PriceHistoryQuery>) -> Result<Json<PriceHistory>, TdaError> {
+    let symbol = symbol.to_uppercase();
+    let (candle_cache, symbol, query) = (&state.candle_cache, &symbol, &query);
+    let price_history = state
+        .token_manager
+        .with_access_token(user.user_id, |token| async move { candle_cache.price_history(&token, symbol, query).await })
+        .await?;
+    Ok(Json(price_history))
+}
@@ -0,0 +1,29 @@
+use crate::{
+    handlers::error::ApiError,
+    middleware::jwt::AuthUser,
+    tda_client::market_data::{Quote, TDAmeritradeClientMarketData},
+    AppState,
+};
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use std::collections::HashMap;
+
+#[derive(serde::Deserialize)]
+pub struct GetQuotesQuery {
+    symbols: String,
+}
+
+pub async fn get_quotes(user: AuthUser, State(state): State<AppState>, Query(query): Query<GetQuotesQuery>) -> Result<Json<HashMap<String, Quote>>, ApiError> {
+    let symbols: Vec<String> = query.symbols.split(',').map(str::trim).filter(|symbol| !symbol.is_empty()).map(str::to_uppercase).collect();
+    if symbols.is_empty() {
+        return Err(ApiError::BadRequest("symbols is required".to_string()));
+    }
+    let (tda_client, symbols) = (&state.tda_client, &symbols);
+    let quotes = state
+        .token_manager
+        .with_access_token(user.user_id, |token| async move { tda_client.get_quotes(&token, symbols).await })
+        .await?;
+    Ok(Json(quotes))
+}
@@ -0,0 +1,28 @@
+use crate::{
+    middleware::jwt::AuthUser,
+    tda_client::{
+        error::TdaError,
+        saved_orders::{SavedOrder, TDAmeritradeClientSavedOrders},
+    },
+    AppState,
+};
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct GetSavedOrderPath {
+    account_id: String,
+    saved_order_id: String,
+}
+
+pub async fn get_saved_order(user: AuthUser, State(state): State<AppState>, Path(path): Path<GetSavedOrderPath>) -> Result<Json<SavedOrder>, TdaError> {
+    let (tda_client, path) = (&state.tda_client, &path);
+    let saved_order = state
+        .token_manager
+        .with_access_token(user.user_id, |token| async move { tda_client.get_saved_order(&token, &path.account_id, &path.saved_order_id).await })
+        .await?;
+    Ok(Json(saved_order))
+}
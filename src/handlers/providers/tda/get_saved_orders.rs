@@ -0,0 +1,27 @@
+use crate::{
+    middleware::jwt::AuthUser,
+    tda_client::{
+        error::TdaError,
+        saved_orders::{SavedOrder, TDAmeritradeClientSavedOrders},
+    },
+    AppState,
+};
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct GetSavedOrdersPath {
+    account_id: String,
+}
+
+pub async fn get_saved_orders(user: AuthUser, State(state): State<AppState>, Path(path): Path<GetSavedOrdersPath>) -> Result<Json<Vec<SavedOrder>>, TdaError> {
+    let (tda_client, path) = (&state.tda_client, &path);
+    let saved_orders = state
+        .token_manager
+        .with_access_token(user.user_id, |token| async move { tda_client.get_saved_orders(&token, &path.account_id).await })
+        .await?;
+    Ok(Json(saved_orders))
+}
@@ -0,0 +1,27 @@
+use crate::{
+    middleware::jwt::AuthUser,
+    tda_client::{
+        error::TdaError,
+        transactions::{TDAmeritradeClientTransactions, Transaction},
+    },
+    AppState,
+};
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+
+#[derive(serde::Deserialize)]
+pub struct GetTransactionPath {
+    account_id: String,
+    transaction_id: String,
+}
+
+pub async fn get_transaction(user: AuthUser, State(state): State<AppState>, Path(path): Path<GetTransactionPath>) -> Result<Json<Transaction>, TdaError> {
+    let (tda_client, path) = (&state.tda_client, &path);
+    let transaction = state
+        .token_manager
+        .with_access_token(user.user_id, |token| async move { tda_client.get_transaction(&token, &path.account_id, &path.transaction_id).await })
+        .await?;
+    Ok(Json(transaction))
+}
@@ -0,0 +1,31 @@
+use crate::{
+    middleware::jwt::AuthUser,
+    tda_client::{
+        error::TdaError,
+        transactions::{GetTransactionsQuery, TDAmeritradeClientTransactions, Transaction},
+    },
+    AppState,
+};
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+
+#[derive(serde::Deserialize)]
+pub struct GetTransactionsPath {
+    account_id: String,
+}
+
+pub async fn get_transactions(
+    user: AuthUser,
+    State(state): State<AppState>,
+    Path(path): Path<GetTransactionsPath>,
+    Query(query): Query<GetTransactionsQuery>,
+) -> Result<Json<Vec<Transaction>>, TdaError> {
+    let (tda_client, path, query) = (&state.tda_client, &path, &query);
+    let transactions = state
+        .token_manager
+        .with_access_token(user.user_id, |token| async move { tda_client.get_transactions(&token, &path.account_id, query).await })
+        .await?;
+    Ok(Json(transactions))
+}
@@ -0,0 +1,27 @@
+use crate::{
+    middleware::jwt::AuthUser,
+    tda_client::{
+        error::TdaError,
+        watchlists::{TDAmeritradeClientWatchlists, Watchlist},
+    },
+    AppState,
+};
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+
+#[derive(serde::Deserialize)]
+pub struct GetWatchlistPath {
+    account_id: String,
+    watchlist_id: String,
+}
+
+pub async fn get_watchlist(user: AuthUser, State(state): State<AppState>, Path(path): Path<GetWatchlistPath>) -> Result<Json<Watchlist>, TdaError> {
+    let (tda_client, path) = (&state.tda_client, &path);
+    let watchlist = state
+        .token_manager
+        .with_access_token(user.user_id, |token| async move { tda_client.get_watchlist(&token, &path.account_id, &path.watchlist_id).await })
+        .await?;
+    Ok(Json(watchlist))
+}
@@ -0,0 +1,19 @@
+use crate::{
+    middleware::jwt::AuthUser,
+    tda_client::{
+        error::TdaError,
+        watchlists::{TDAmeritradeClientWatchlists, Watchlist},
+    },
+    AppState,
+};
+use axum::{extract::State, Json};
+
+/// Watchlists of every account linked to the user's TDA login.
+pub async fn get_watchlists(user: AuthUser, State(state): State<AppState>) -> Result<Json<Vec<Watchlist>>, TdaError> {
+    let tda_client = &state.tda_client;
+    let watchlists = state
+        .token_manager
+        .with_access_token(user.user_id, |token| async move { tda_client.get_watchlists_for_all_accounts(&token).await })
+        .await?;
+    Ok(Json(watchlists))
+}
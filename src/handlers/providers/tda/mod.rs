@@ -1,7 +1,63 @@
 pub mod auth;
+pub mod cancel_order;
+pub use cancel_order::cancel_order;
+pub mod create_watchlist;
+pub use create_watchlist::create_watchlist;
+pub mod create_saved_order;
+pub use create_saved_order::create_saved_order;
+pub mod delete_saved_order;
+pub use delete_saved_order::delete_saved_order;
+pub mod delete_watchlist;
+pub use delete_watchlist::delete_watchlist;
+pub mod get_account;
+pub use get_account::get_account;
+pub mod get_account_display_names;
+pub use get_account_display_names::get_account_display_names;
+pub mod get_account_watchlists;
+pub use get_account_watchlists::get_account_watchlists;
 pub mod get_accounts;
 pub use get_accounts::get_accounts;
+pub mod get_all_orders;
+pub use get_all_orders::get_all_orders;
+pub mod get_instrument;
+pub use get_instrument::get_instrument;
+pub mod get_movers;
+pub use get_movers::get_movers;
+pub mod get_option_chain;
+pub use get_option_chain::get_option_chain;
+pub mod get_order;
+pub use get_order::get_order;
 pub mod get_orders;
 pub use get_orders::get_orders;
+pub mod get_price_history;
+pub use get_price_history::get_price_history;
+pub mod get_quotes;
+pub use get_quotes::get_quotes;
+pub mod get_saved_order;
+pub use get_saved_order::get_saved_order;
+pub mod get_saved_orders;
+pub use get_saved_orders::get_saved_orders;
+pub mod get_transaction;
+pub use get_transaction::get_transaction;
+pub mod get_transactions;
+pub use get_transactions::get_transactions;
+pub mod get_watchlist;
+pub use get_watchlist::get_watchlist;
+pub mod get_watchlists;
+pub use get_watchlists::get_watchlists;
+pub mod place_order;
+pub use place_order::place_order;
 pub mod refresh_token;
 pub use refresh_token::auth_tda_refresh_token;
+pub mod replace_order;
+pub use replace_order::replace_order;
+pub mod replace_saved_order;
+pub use replace_saved_order::replace_saved_order;
+pub mod replace_watchlist;
+pub use replace_watchlist::replace_watchlist;
+pub mod search_instruments;
+pub use search_instruments::search_instruments;
+pub mod stream;
+pub use stream::stream;
+pub mod update_watchlist;
+pub use update_watchlist::update_watchlist;
@@ -0,0 +1,36 @@
+use crate::{
+    middleware::jwt::AuthUser,
+    tda_client::{
+        error::TdaError,
+        orders::{OrderRequest, TDAmeritradeClientOrders},
+    },
+    AppState,
+};
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    Json,
+};
+use hyper::StatusCode;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize)]
+pub struct PlaceOrderPath {
+    account_id: String,
+}
+
+/// `order_id` is `None` when TDA accepted the order without saying which id it got; the
+/// order is live either way, so clients must not resend it.
+#[derive(Serialize)]
+pub struct PlaceOrderResponse {
+    pub order_id: Option<i64>,
+}
+
+pub async fn place_order(user: AuthUser, State(state): State<AppState>, Path(path): Path<PlaceOrderPath>, Json(order): Json<OrderRequest>) -> Result<impl IntoResponse, TdaError> {
+    let (tda_client, path, order) = (&state.tda_client, &path, &order);
+    let order_id = state
+        .token_manager
+        .with_access_token(user.user_id, |token| async move { tda_client.place_order(&token, &path.account_id, order).await })
+        .await?;
+    Ok((StatusCode::CREATED, Json(PlaceOrderResponse { order_id })))
+}
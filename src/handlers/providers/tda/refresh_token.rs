@@ -1,32 +1,26 @@
-use crate::{
-    tda_client::auth::{TDAmeritradeClientAuth, TokenResponse},
-    utils::cookie::{create_access_token, create_refresh_token},
-    AppState,
-};
+use crate::{middleware::jwt::AuthUser, tda_client::auth::TokenResponse, AppState};
 use axum::{
     extract::{Json, State},
-    response::IntoResponse,
+    response::{IntoResponse, Response},
 };
-use axum_extra::extract::CookieJar;
+use chrono::Utc;
 use hyper::StatusCode;
 use log::error;
 
-pub async fn auth_tda_refresh_token(jar: CookieJar, State(state): State<AppState>) -> impl IntoResponse {
-    let refresh_token_cookie = jar.get("refresh_token_tda");
-    let refresh_token = match refresh_token_cookie {
-        Some(cookie) => cookie.value(),
-        None => return (StatusCode::UNAUTHORIZED, jar, Json(TokenResponse::default())),
-    };
-    let token_response = match state.tda_client.exchange_refresh_token_for_token(&refresh_token).await {
-        Ok(data) => data,
+pub async fn auth_tda_refresh_token(user: AuthUser, State(state): State<AppState>) -> Response {
+    let token = match state.token_manager.refresh(user.user_id, None).await {
+        Ok(token) => token,
         Err(e) => {
             error!("auth_refresh_token error: {}", e);
-            return (StatusCode::INTERNAL_SERVER_ERROR, jar, Json(TokenResponse::default()));
+            return e.into_response();
         }
     };
-    let cloned_token_response = token_response.clone();
-    let access_token = cloned_token_response.access_token.unwrap_or_default();
-    let refresh_token = cloned_token_response.refresh_token.unwrap_or_default();
-    let jar = jar.add(create_access_token(access_token)).add(create_refresh_token(refresh_token));
-    return (StatusCode::OK, jar, Json(token_response));
+    // Tokens stay server side; the client only learns when they expire.
+    let expires_in = (token.expires_at - Utc::now().naive_utc()).num_seconds().max(0) as u64;
+    let token_response = TokenResponse {
+        token_type: Some("Bearer".to_string()),
+        expires_in: Some(expires_in),
+        ..TokenResponse::default()
+    };
+    (StatusCode::OK, Json(token_response)).into_response()
 }
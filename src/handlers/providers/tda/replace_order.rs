@@ -0,0 +1,37 @@
+use crate::{
+    middleware::jwt::AuthUser,
+    tda_client::{
+        error::TdaError,
+        orders::{OrderRequest, TDAmeritradeClientOrders},
+    },
+    AppState,
+};
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    Json,
+};
+use hyper::StatusCode;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize)]
+pub struct ReplaceOrderPath {
+    account_id: String,
+    order_id: String,
+}
+
+/// `order_id` is the replacement's id, or `None` when TDA did not say; the replacement
+/// is live either way.
+#[derive(Serialize)]
+pub struct ReplaceOrderResponse {
+    pub order_id: Option<i64>,
+}
+
+pub async fn replace_order(user: AuthUser, State(state): State<AppState>, Path(path): Path<ReplaceOrderPath>, Json(order): Json<OrderRequest>) -> Result<impl IntoResponse, TdaError> {
+    let (tda_client, path, order) = (&state.tda_client, &path, &order);
+    let order_id = state
+        .token_manager
+        .with_access_token(user.user_id, |token| async move { tda_client.replace_order(&token, &path.account_id, &path.order_id, order).await })
+        .await?;
+    Ok((StatusCode::CREATED, Json(ReplaceOrderResponse { order_id })))
+}
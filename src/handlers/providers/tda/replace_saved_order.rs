@@ -0,0 +1,28 @@
+use crate::{
+    middleware::jwt::AuthUser,
+    tda_client::{error::TdaError, orders::OrderRequest, saved_orders::TDAmeritradeClientSavedOrders},
+    AppState,
+};
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use hyper::StatusCode;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct ReplaceSavedOrderPath {
+    account_id: String,
+    saved_order_id: String,
+}
+
+pub async fn replace_saved_order(user: AuthUser, State(state): State<AppState>, Path(path): Path<ReplaceSavedOrderPath>, Json(order): Json<OrderRequest>) -> Result<StatusCode, TdaError> {
+    let (tda_client, path, order) = (&state.tda_client, &path, &order);
+    state
+        .token_manager
+        .with_access_token(user.user_id, |token| async move {
+            tda_client.replace_saved_order(&token, &path.account_id, &path.saved_order_id, order).await
+        })
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
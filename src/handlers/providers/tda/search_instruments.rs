@@ -0,0 +1,51 @@
+use crate::{
+    handlers::error::ApiError,
+    middleware::jwt::AuthUser,
+    tda_client::instruments::{InstrumentInfo, Projection, TDAmeritradeClientInstruments},
+    AppState,
+};
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+
+#[derive(serde::Deserialize)]
+pub struct SearchInstrumentsQuery {
+    symbol: String,
+    #[serde(default)]
+    projection: Projection,
+}
+
+/// Backs ticker autocomplete and validation in the journal, e.g.
+/// `?symbol=AAP.*&projection=symbol-regex`.
+pub async fn search_instruments(user: AuthUser, State(state): State<AppState>, Query(query): Query<SearchInstrumentsQuery>) -> Result<Json<Vec<InstrumentInfo>>, ApiError> {
+    let symbol = query.symbol.trim();
+    if symbol.is_empty() {
+        return Err(ApiError::BadRequest("symbol is required".to_string()));
+    }
+    let symbol = match query.projection {
+        // Uppercasing a regex would turn escapes like `\d` into their negation `\D`.
+        Projection::SymbolRegex | Projection::DescSearch | Projection::DescRegex => symbol.to_string(),
+        _ => symbol.to_uppercase(),
+    };
+    let (tda_client, symbol, projection) = (&state.tda_client, &symbol, query.projection);
+    let instruments = state
+        .token_manager
+        .with_access_token(user.user_id, |token| async move { tda_client.search_instruments(&token, symbol, projection).await })
+        .await?;
+    Ok(Json(instruments))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tda_client::mock::{authed_server, MockTda};
+
+    #[tokio::test]
+    async fn test_search_instruments_keeps_regex_case() {
+        let tda = MockTda::start();
+        let (server, cookie) = authed_server(&tda).await;
+        server.get("/api/instruments?symbol=aapl").add_cookie(cookie.clone()).await;
+        server.get("/api/instruments?symbol=AAP%5Cd%2B&projection=symbol-regex").add_cookie(cookie).await;
+        assert_eq!(tda.queries(), vec!["symbol=AAPL&projection=symbol-search", "symbol=AAP%5Cd%2B&projection=symbol-regex"]);
+    }
+}
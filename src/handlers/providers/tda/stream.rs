@@ -0,0 +1,157 @@
+use crate::{
+    middleware::jwt::AuthUser,
+    tda_client::{
+        stream_hub::StreamClient,
+        streamer::{StreamUpdate, StreamerError},
+    },
+    AppState,
+};
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        State,
+    },
+    response::Response,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::time::Duration;
+use tokio::time::{interval_at, Instant};
+
+/// A client that doesn't answer a ping by the next one is dropped.
+const PING_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+enum ClientCommand {
+    Subscribe { symbols: Vec<String> },
+    Unsubscribe { symbols: Vec<String> },
+}
+
+/// Streams account activity and level-one quotes. Clients send
+/// `{"action":"subscribe","symbols":["AAPL"]}` (or `unsubscribe`) and receive
+/// [`StreamUpdate`]s as JSON, starting with the current connection status.
+pub async fn stream(user: AuthUser, State(state): State<AppState>, ws: WebSocketUpgrade) -> Response {
+    let stream_hub = state.stream_hub.clone();
+    ws.on_upgrade(move |socket| forward(socket, stream_hub.connect(user.user_id)))
+}
+
+async fn send<T: Serialize>(socket: &mut WebSocket, message: &T) -> Result<(), StreamerError> {
+    let text = serde_json::to_string(message).unwrap_or_default();
+    socket.send(Message::Text(text)).await.map_err(|_| StreamerError::Closed)
+}
+
+async fn forward(mut socket: WebSocket, mut client: StreamClient) {
+    if send(&mut socket, &StreamUpdate::Status { connected: client.is_connected() }).await.is_err() {
+        return;
+    }
+    let mut ping = interval_at(Instant::now() + PING_INTERVAL, PING_INTERVAL);
+    let mut alive = true;
+    loop {
+        let sent = tokio::select! {
+            message = socket.recv() => {
+                let text = match message {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                    Some(Ok(_)) => {
+                        alive = true;
+                        continue;
+                    }
+                };
+                alive = true;
+                match serde_json::from_str::<ClientCommand>(&text) {
+                    Ok(ClientCommand::Subscribe { symbols }) => {
+                        client.subscribe(&symbols.iter().map(|symbol| symbol.trim().to_uppercase()).collect::<Vec<String>>());
+                        Ok(())
+                    }
+                    Ok(ClientCommand::Unsubscribe { symbols }) => {
+                        client.unsubscribe(&symbols.iter().map(|symbol| symbol.trim().to_uppercase()).collect::<Vec<String>>());
+                        Ok(())
+                    }
+                    Err(e) => send(&mut socket, &json!({ "type": "error", "message": e.to_string() })).await,
+                }
+            }
+            update = client.recv() => match update {
+                Some(update) => send(&mut socket, &update).await,
+                None => return,
+            },
+            _ = ping.tick() => {
+                if !alive {
+                    return;
+                }
+                alive = false;
+                socket.send(Message::Ping(vec![])).await.map_err(|_| StreamerError::Closed)
+            }
+        };
+        if sent.is_err() {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        router::Router,
+        tda_client::mock::{authed_state, MockTda},
+    };
+    use axum::http::header::COOKIE;
+    use futures_util::{SinkExt, StreamExt};
+    use serde_json::{json, Value};
+    use std::{net::TcpListener, time::Duration};
+    use tokio::{net::TcpStream, time::timeout};
+    use tokio_tungstenite::{
+        connect_async,
+        tungstenite::{client::IntoClientRequest, Message},
+        MaybeTlsStream, WebSocketStream,
+    };
+
+    type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+    async fn next_matching(socket: &mut Socket, predicate: impl Fn(&Value) -> bool) -> Value {
+        timeout(Duration::from_secs(5), async {
+            loop {
+                if let Some(Ok(Message::Text(text))) = socket.next().await {
+                    let message = serde_json::from_str::<Value>(&text).unwrap();
+                    if predicate(&message) {
+                        return message;
+                    }
+                }
+            }
+        })
+        .await
+        .expect("no matching message")
+    }
+
+    #[tokio::test]
+    async fn test_stream_quotes() {
+        let tda = MockTda::start();
+        let (state, access_token) = authed_state(&tda).await;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = axum::Server::from_tcp(listener).unwrap().serve(Router::new(state).get_router().into_make_service());
+        tokio::spawn(server);
+
+        let url = format!("ws://{}/api/stream", addr);
+        assert!(connect_async(url.as_str()).await.is_err());
+
+        let mut request = url.into_client_request().unwrap();
+        request.headers_mut().insert(COOKIE, format!("access_token={}", access_token).parse().unwrap());
+        let (mut socket, _) = connect_async(request).await.unwrap();
+        next_matching(&mut socket, |message| *message == json!({"type": "status", "connected": true})).await;
+
+        socket.send(Message::Text(json!({"action": "subscribe", "symbols": ["aapl"]}).to_string())).await.unwrap();
+        let quote = next_matching(&mut socket, |message| message["type"] == "quote").await;
+        assert_eq!(quote["symbol"], "AAPL");
+        assert!(quote["bidPrice"].is_number());
+
+        socket.send(Message::Text(json!({"action": "watch"}).to_string())).await.unwrap();
+        next_matching(&mut socket, |message| message["type"] == "error").await;
+
+        tda.push_account_activity("OrderEntryRequest", "<OrderEntryRequestMessage/>");
+        let activity = next_matching(&mut socket, |message| message["type"] == "account_activity").await;
+        assert_eq!(activity["messageType"], "OrderEntryRequest");
+        socket.close(None).await.unwrap();
+    }
+}
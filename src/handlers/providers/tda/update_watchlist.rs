@@ -0,0 +1,31 @@
+use crate::{
+    middleware::jwt::AuthUser,
+    tda_client::{
+        error::TdaError,
+        watchlists::{TDAmeritradeClientWatchlists, WatchlistRequest},
+    },
+    AppState,
+};
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use hyper::StatusCode;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct UpdateWatchlistPath {
+    account_id: String,
+    watchlist_id: String,
+}
+
+pub async fn update_watchlist(user: AuthUser, State(state): State<AppState>, Path(path): Path<UpdateWatchlistPath>, Json(watchlist): Json<WatchlistRequest>) -> Result<StatusCode, TdaError> {
+    let (tda_client, path, watchlist) = (&state.tda_client, &path, &watchlist);
+    state
+        .token_manager
+        .with_access_token(user.user_id, |token| async move {
+            tda_client.update_watchlist(&token, &path.account_id, &path.watchlist_id, watchlist).await
+        })
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
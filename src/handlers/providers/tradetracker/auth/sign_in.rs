@@ -7,6 +7,7 @@ use argon2::{Argon2, PasswordHash, PasswordVerifier};
 use axum::{extract::State, response::IntoResponse, Json};
 use axum_extra::extract::CookieJar;
 use hyper::StatusCode;
+use log::error;
 use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize)]
@@ -33,19 +34,85 @@ pub struct AuthSignInWithEmailPasswordResponse {
 }
 
 pub async fn auth_sign_in_with_email_password(state: State<AppState>, jar: CookieJar, json: Json<AuthSignInWithEmailPasswordRequest>) -> impl IntoResponse {
-    let db_user_auth = state.database_client.get_user_auth_by_email(json.email.as_str());
-    if db_user_auth.is_none() {
-        return (StatusCode::BAD_REQUEST, jar, Json(AuthSignInWithEmailPasswordResponse::default()));
-    }
-    let db_user_auth = db_user_auth.unwrap();
+    let db_user_auth = match state.users.get_user_auth_by_email(json.email.as_str()).await {
+        Ok(Some(db_user_auth)) => db_user_auth,
+        Ok(None) => return (StatusCode::BAD_REQUEST, jar, Json(AuthSignInWithEmailPasswordResponse::default())),
+        Err(e) => {
+            error!("get_user_auth_by_email failed: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, jar, Json(AuthSignInWithEmailPasswordResponse::default()));
+        }
+    };
     let ok = verify_password(json.password.clone(), db_user_auth.password_hash);
     if !ok {
         return (StatusCode::BAD_REQUEST, jar, Json(AuthSignInWithEmailPasswordResponse::default()));
     }
-    let access_token = create_access_token(state.env.jwt_access_token_secret.as_str());
-    let refresh_token = create_refresh_token(state.env.jwt_refresh_token_secret.as_str());
+    let access_token = create_access_token(db_user_auth.user_id, state.env.jwt_access_token_secret.as_str());
+    let refresh_token = create_refresh_token(db_user_auth.user_id, state.env.jwt_refresh_token_secret.as_str());
     let access_token_cookie = cookie::create_access_token(access_token.clone());
     let refresh_token_cookie = cookie::create_refresh_token(refresh_token.clone());
     let jar = jar.add(access_token_cookie).add(refresh_token_cookie);
     (StatusCode::OK, jar, Json(AuthSignInWithEmailPasswordResponse { access_token, refresh_token }))
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{router::Router, tda_client::mock::MockTda, AppState};
+    use axum_test::TestServer;
+    use cookie::Cookie;
+    use hyper::StatusCode;
+    use serde_json::{json, Value};
+
+    fn server(tda: &MockTda) -> TestServer {
+        let router = Router::new(AppState::in_memory(tda.client())).get_router();
+        TestServer::new(router.into_make_service()).unwrap()
+    }
+
+    fn sign_up_body(email: &str, password: &str) -> Value {
+        json!({ "cf-turnstile-response": "", "email": email, "password": password })
+    }
+
+    #[tokio::test]
+    async fn test_sign_up_and_sign_in() {
+        let tda = MockTda::start();
+        let server = server(&tda);
+        let body = sign_up_body("trader@example.com", "hunter22");
+        let res = server.post("/api/auth/providers/tradetracker/signup").json(&body).await;
+        assert_eq!(res.status_code(), StatusCode::OK);
+        let res = server.post("/api/auth/providers/tradetracker/signup").json(&body).expect_failure().await;
+        assert_eq!(res.status_code(), StatusCode::BAD_REQUEST);
+
+        let res = server
+            .post("/api/auth/providers/tradetracker/signin")
+            .json(&json!({ "email": "trader@example.com", "password": "hunter22" }))
+            .await;
+        assert_eq!(res.status_code(), StatusCode::OK);
+        let body = res.json::<Value>();
+        assert!(!body["access_token"].as_str().unwrap().is_empty());
+        assert_eq!(res.cookie("access_token").value(), body["access_token"].as_str().unwrap());
+
+        let res = server
+            .post("/api/auth/providers/tradetracker/signin")
+            .json(&json!({ "email": "trader@example.com", "password": "wrong" }))
+            .expect_failure()
+            .await;
+        assert_eq!(res.status_code(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_access_token_authenticates_private_routes() {
+        let tda = MockTda::start();
+        let server = server(&tda);
+        server.post("/api/auth/providers/tradetracker/signup").json(&sign_up_body("trader@example.com", "hunter22")).await;
+        let res = server
+            .post("/api/auth/providers/tradetracker/signin")
+            .json(&json!({ "email": "trader@example.com", "password": "hunter22" }))
+            .await;
+        let access_token = res.json::<Value>()["access_token"].as_str().unwrap().to_string();
+
+        let res = server.post("/api/auth/providers/tda").expect_failure().await;
+        assert_eq!(res.status_code(), StatusCode::IM_A_TEAPOT);
+        // Signed in, but the user never connected TD Ameritrade.
+        let res = server.post("/api/auth/providers/tda").add_cookie(Cookie::new("access_token", access_token)).expect_failure().await;
+        assert_eq!(res.status_code(), StatusCode::UNAUTHORIZED);
+    }
+}
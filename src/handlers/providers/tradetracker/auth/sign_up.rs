@@ -1,5 +1,5 @@
 use crate::{
-    database_client::{CreateUser, CreateUserAuth},
+    database_client::{error::DatabaseError, CreateUser, CreateUserAuth},
     middleware::cloudflare::verify_cf_response,
     AppState,
 };
@@ -56,11 +56,15 @@ pub async fn auth_sign_up_with_email_password(state: State<AppState>, json: Json
     }
     let password_hash = password_hash.unwrap();
     let create_user_auth = CreateUserAuth { password_hash };
-    let result = state.database_client.create_user_and_user_auth(create_user, create_user_auth);
-    if result.is_err() {
-        let error = result.err().unwrap();
-        error!("create_user_and_user_auth failed: {:?}", error);
-        return StatusCode::BAD_REQUEST;
+    match state.users.create_user_and_user_auth(create_user, create_user_auth).await {
+        Ok(()) => StatusCode::OK,
+        Err(DatabaseError::Conflict(e)) => {
+            error!("create_user_and_user_auth conflict: {}", e);
+            StatusCode::BAD_REQUEST
+        }
+        Err(e) => {
+            error!("create_user_and_user_auth failed: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
     }
-    StatusCode::OK
 }
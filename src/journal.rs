@@ -0,0 +1,105 @@
+use crate::{
+    database_client::{models::TradeExecution, UpsertTrade},
+    tda_client::accounts::{Order, OrderGet},
+};
+use chrono::{DateTime, NaiveDateTime};
+use log::warn;
+use serde::Serialize;
+use uuid::Uuid;
+
+/// Journal trades for every filled leg in `orders`, including fills of child and
+/// replacing orders. Orders that never filled, like most cancels, yield nothing.
+pub fn trades_from_orders(user_id: Uuid, orders: &[Order]) -> Vec<UpsertTrade> {
+    orders
+        .iter()
+        .flat_map(|Order::OrderGet(order)| order.flatten())
+        .flat_map(|order| trades_from_order(user_id, order))
+        .collect()
+}
+
+fn trades_from_order(user_id: Uuid, order: &OrderGet) -> Vec<UpsertTrade> {
+    let mut trades = vec![];
+    // Executions refer to legs by id, so legs without one cannot be matched to fills.
+    for (leg, leg_id) in order.order_leg_collection.iter().filter_map(|leg| leg.leg_id.map(|leg_id| (leg, leg_id))) {
+        let mut executions = vec![];
+        for execution in order.executions() {
+            for execution_leg in execution.execution_legs.iter().filter(|execution_leg| execution_leg.leg_id == leg_id) {
+                let filled_at = match parse_time(&execution_leg.time) {
+                    Some(filled_at) => filled_at,
+                    None => {
+                        warn!("Skipping fill of order {} with unreadable time {}", order.order_id, execution_leg.time);
+                        continue;
+                    }
+                };
+                executions.push(TradeExecution {
+                    sequence: executions.len() as u32,
+                    activity_id: execution.activity_id,
+                    quantity: execution_leg.quantity,
+                    price: execution_leg.price,
+                    filled_at,
+                });
+            }
+        }
+        let quantity: f64 = executions.iter().map(|execution| execution.quantity).sum();
+        if quantity <= 0.0 {
+            continue;
+        }
+        let price = executions.iter().map(|execution| execution.quantity * execution.price).sum::<f64>() / quantity;
+        let first_filled_at = executions.iter().map(|execution| execution.filled_at).min().unwrap();
+        let last_filled_at = executions.iter().map(|execution| execution.filled_at).max().unwrap();
+        trades.push(UpsertTrade {
+            user_id,
+            account_id: order.account_id,
+            order_id: order.order_id,
+            leg_id,
+            symbol: leg.instrument.symbol().to_string(),
+            asset_type: name(&leg.instrument.asset_type()),
+            instruction: name(&leg.instruction),
+            position_effect: leg.position_effect.as_ref().map(name),
+            quantity,
+            price,
+            first_filled_at,
+            last_filled_at,
+            executions,
+        });
+    }
+    trades
+}
+
+/// The name TDA uses for an enum value, e.g. `BUY_TO_OPEN`.
+fn name<T: Serialize>(value: &T) -> String {
+    serde_json::to_value(value).ok().and_then(|value| value.as_str().map(str::to_string)).unwrap_or_default()
+}
+
+/// TDA times look like `2023-03-01T15:30:12+0000`; the journal stores them as UTC.
+fn parse_time(time: &str) -> Option<NaiveDateTime> {
+    DateTime::parse_from_str(time, "%Y-%m-%dT%H:%M:%S%z").ok().map(|time| time.naive_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::trades_from_orders;
+    use crate::tda_client::accounts::{Order, OrderGet};
+    use chrono::NaiveDate;
+    use uuid::Uuid;
+
+    const ORDERS: &str = include_str!("tda_client/fixtures/orders.json");
+
+    #[test]
+    fn test_trades_from_orders() {
+        let orders: Vec<Order> = serde_json::from_str::<Vec<OrderGet>>(ORDERS).unwrap().into_iter().map(Order::OrderGet).collect();
+        let user_id = Uuid::new_v4();
+        let trades = trades_from_orders(user_id, &orders);
+
+        let order_ids: Vec<i64> = trades.iter().map(|trade| trade.order_id).collect();
+        assert_eq!(order_ids, vec![1001, 1011, 1008]);
+        let msft = &trades[1];
+        assert_eq!((msft.symbol.as_str(), msft.instruction.as_str(), msft.asset_type.as_str()), ("MSFT", "BUY", "EQUITY"));
+        assert_eq!((msft.quantity, msft.price), (5.0, 247.95));
+        assert_eq!(msft.first_filled_at, NaiveDate::from_ymd_opt(2023, 3, 2).unwrap().and_hms_opt(15, 10, 4).unwrap());
+        assert_eq!(msft.executions[0].activity_id, Some(2011));
+        let option = &trades[2];
+        assert_eq!((option.account_id, option.asset_type.as_str(), option.instruction.as_str()), (987654321, "OPTION", "BUY_TO_OPEN"));
+        assert_eq!(option.position_effect.as_deref(), Some("OPENING"));
+    }
+}
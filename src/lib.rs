@@ -1,41 +1,96 @@
-use database_client::DatabaseClient;
-use tda_client::TDAmeritradeClient;
+use database_client::{
+    memory::InMemoryDatabase,
+    repository::{BrokerConnectionRepository, CandleRepository, EncryptedBrokerConnections, TradeRepository, UserRepository},
+    DatabaseClient,
+};
+use std::sync::Arc;
+use tda_client::{candle_cache::CandleCache, movers_cache::MoversCache, stream_hub::StreamHub, token_manager::TokenManager, TDAmeritradeClient};
 
 #[derive(Clone)]
 struct Env {
     jwt_access_token_secret: String,
     jwt_refresh_token_secret: String,
+    /// Signs OAuth `state` tokens. Derived from the access token secret, but a different
+    /// key, so a state token never verifies as an access token or the other way round.
+    jwt_oauth_state_secret: String,
 }
 
 impl Env {
     pub fn new() -> Self {
         let jwt_access_token_secret = std::env::var("JWT_ACCESS_TOKEN_SECRET").expect("JWT_ACCESS_TOKEN_SECRET must be set");
         let jwt_refresh_token_secret = std::env::var("JWT_REFRESH_TOKEN_SECRET").expect("JWT_REFRESH_TOKEN_SECRET must be set");
+        Self::with_secrets(jwt_access_token_secret, jwt_refresh_token_secret)
+    }
+
+    fn with_secrets(jwt_access_token_secret: String, jwt_refresh_token_secret: String) -> Self {
+        let jwt_oauth_state_secret = format!("{}:oauth_state", jwt_access_token_secret);
         Self {
             jwt_access_token_secret,
             jwt_refresh_token_secret,
+            jwt_oauth_state_secret,
         }
     }
 }
 
 #[derive(Clone)]
 pub struct AppState {
-    database_client: DatabaseClient,
+    users: Arc<dyn UserRepository>,
     env: Env,
     tda_client: TDAmeritradeClient,
+    token_manager: TokenManager,
+    candle_cache: CandleCache,
+    movers_cache: MoversCache,
+    stream_hub: StreamHub,
+    trades: Arc<dyn TradeRepository>,
 }
 
 impl AppState {
-    pub fn new() -> Self {
+    pub async fn new() -> Self {
         let tda_client = TDAmeritradeClient::new();
         let env = Env::new();
         let database_client = DatabaseClient::new();
-        Self { database_client, env, tda_client }
+        database_client.migrate_up_async().await.expect("Error applying database migrations");
+        let broker_connections = Arc::new(EncryptedBrokerConnections::from_env(database_client.clone()));
+        let database_client = Arc::new(database_client);
+        Self::with_repositories(env, tda_client, database_client.clone(), broker_connections, database_client.clone(), database_client)
+    }
+
+    /// State backed by [`InMemoryDatabase`] and fixed secrets, for tests that run without MySQL.
+    /// Pair it with a client for the test-only `tda_client::mock::MockTda` to avoid the network as well.
+    pub fn in_memory(tda_client: TDAmeritradeClient) -> Self {
+        let env = Env::with_secrets("test-access-token-secret".to_string(), "test-refresh-token-secret".to_string());
+        let database = Arc::new(InMemoryDatabase::default());
+        Self::with_repositories(env, tda_client, database.clone(), database.clone(), database.clone(), database)
+    }
+
+    fn with_repositories(
+        env: Env,
+        tda_client: TDAmeritradeClient,
+        users: Arc<dyn UserRepository>,
+        broker_connections: Arc<dyn BrokerConnectionRepository>,
+        candles: Arc<dyn CandleRepository>,
+        trades: Arc<dyn TradeRepository>,
+    ) -> Self {
+        let token_manager = TokenManager::new(tda_client.clone(), broker_connections);
+        let candle_cache = CandleCache::new(tda_client.clone(), candles);
+        let movers_cache = MoversCache::new();
+        let stream_hub = StreamHub::new(tda_client.clone(), token_manager.clone());
+        Self {
+            users,
+            env,
+            tda_client,
+            token_manager,
+            candle_cache,
+            movers_cache,
+            stream_hub,
+            trades,
+        }
     }
 }
 
 pub mod database_client;
 pub mod handlers;
+pub mod journal;
 pub mod middleware;
 pub mod router;
 pub mod server;
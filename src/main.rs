@@ -1,16 +1,54 @@
 use dotenv::dotenv;
 use env_logger::Builder;
-use tda_server::{router::Router, server, AppState};
+use std::process::ExitCode;
+use tda_server::{database_client::DatabaseClient, router::Router, server, AppState};
+
+fn migrate(command: Option<&str>) -> ExitCode {
+    let database_client = DatabaseClient::new();
+    let result = match command {
+        Some("up") => database_client.migrate_up().map(|versions| println!("Applied {} migration(s): {:?}", versions.len(), versions)),
+        Some("down") => database_client.migrate_down().map(|version| match version {
+            Some(version) => println!("Reverted migration {}", version),
+            None => println!("No migrations to revert"),
+        }),
+        Some("status") => database_client.migration_status().map(|statuses| {
+            for status in statuses {
+                match status.applied_at {
+                    Some(applied_at) => println!("{:04} {} applied at {}", status.version, status.name, applied_at),
+                    None => println!("{:04} {} pending", status.version, status.name),
+                }
+            }
+        }),
+        _ => {
+            eprintln!("Usage: tda-server migrate <up|down|status>");
+            return ExitCode::FAILURE;
+        }
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Migration failed: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
 
 #[tokio::main]
-async fn main() {
+async fn main() -> ExitCode {
     dotenv().ok();
     dotenv::from_filename(".env.development").unwrap_or_default();
     Builder::new().parse_env("LOG_LEVEL").init();
 
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("migrate") {
+        let command = args.get(1).cloned();
+        return tokio::task::spawn_blocking(move || migrate(command.as_deref())).await.unwrap_or(ExitCode::FAILURE);
+    }
+
     let server = server::Server::new();
-    let app_state = AppState::new();
+    let app_state = AppState::new().await;
     let router = Router::new(app_state);
 
     server.start(router).await;
+    ExitCode::SUCCESS
 }
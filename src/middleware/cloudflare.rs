@@ -3,6 +3,7 @@ use axum::{middleware::Next, response::Response, Json};
 use hyper::Request;
 use serde::{Deserialize, Serialize};
 
+#[cfg_attr(debug_assertions, allow(dead_code))]
 #[derive(Deserialize, PartialEq)]
 struct SiteVerifyResponseSuccess {
     success: bool,
@@ -14,6 +15,7 @@ struct SiteVerifyResponseSuccess {
     cdata: String,
 }
 
+#[cfg_attr(debug_assertions, allow(dead_code))]
 #[derive(Debug, Deserialize, PartialEq)]
 struct SiteVerifyResponseError {
     success: bool,
@@ -21,6 +23,7 @@ struct SiteVerifyResponseError {
     error_codes: Vec<String>,
 }
 
+#[cfg_attr(debug_assertions, allow(dead_code))]
 #[derive(Deserialize, PartialEq)]
 #[serde(untagged)]
 enum SiteVerifyResponse {
@@ -28,6 +31,7 @@ enum SiteVerifyResponse {
     Error(SiteVerifyResponseError),
 }
 
+#[cfg_attr(debug_assertions, allow(dead_code))]
 #[derive(Serialize)]
 struct SiteVerifyBody {
     response: String,
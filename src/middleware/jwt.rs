@@ -1,6 +1,7 @@
+use async_trait::async_trait;
 use axum::{
-    extract::State,
-    http::{header, Request, StatusCode},
+    extract::{FromRequestParts, State},
+    http::{header, request::Parts, Request, StatusCode},
     middleware::Next,
     response::IntoResponse,
 };
@@ -8,22 +9,76 @@ use axum::{
 use axum_extra::extract::cookie::CookieJar;
 use jsonwebtoken::{decode, DecodingKey, Validation};
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 use crate::AppState;
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+pub const ISSUER: &str = "tradetracker";
+pub const AUDIENCE: &str = "tradetracker-api";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenType {
+    Access,
+    Refresh,
+    OauthState,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct TokenClaims {
-    pub sub: String,
+    pub sub: Uuid,
+    pub token_type: TokenType,
+    pub iss: String,
+    pub aud: String,
     pub iat: i64,
     pub exp: i64,
+    pub jti: Uuid,
+}
+
+impl TokenClaims {
+    fn new(user_id: Uuid, token_type: TokenType, expires_in: i64) -> Self {
+        let now = chrono::Utc::now().timestamp();
+        Self {
+            sub: user_id,
+            token_type,
+            iss: ISSUER.to_string(),
+            aud: AUDIENCE.to_string(),
+            iat: now,
+            exp: now + expires_in,
+            jti: Uuid::new_v4(),
+        }
+    }
+}
+
+/// The authenticated caller, inserted into request extensions by [`auth`].
+#[derive(Clone, Debug)]
+pub struct AuthUser {
+    pub user_id: Uuid,
+    pub token_id: Uuid,
 }
 
-pub fn validate_token(token: &str, secret: &str) -> Result<TokenClaims, jsonwebtoken::errors::Error> {
-    let validation = Validation::default();
+#[async_trait]
+impl<S> FromRequestParts<S> for AuthUser
+where
+    S: Send + Sync,
+{
+    type Rejection = StatusCode;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts.extensions.get::<AuthUser>().cloned().ok_or(StatusCode::UNAUTHORIZED)
+    }
+}
+
+pub fn validate_token(token: &str, secret: &str, token_type: TokenType) -> Result<TokenClaims, jsonwebtoken::errors::Error> {
+    let mut validation = Validation::default();
+    validation.set_issuer(&[ISSUER]);
+    validation.set_audience(&[AUDIENCE]);
     let decoding_key = DecodingKey::from_secret(secret.as_ref());
-    match decode::<TokenClaims>(token, &decoding_key, &validation) {
-        Ok(token_data) => Ok(token_data.claims),
-        Err(err) => Err(err),
+    let claims = decode::<TokenClaims>(token, &decoding_key, &validation)?.claims;
+    if claims.token_type == token_type {
+        Ok(claims)
+    } else {
+        Err(jsonwebtoken::errors::Error::from(jsonwebtoken::errors::ErrorKind::InvalidToken))
     }
 }
 
@@ -31,55 +86,88 @@ fn create_token(claims: TokenClaims, secret: &str) -> String {
     jsonwebtoken::encode(&jsonwebtoken::Header::default(), &claims, &jsonwebtoken::EncodingKey::from_secret(secret.as_ref())).unwrap()
 }
 
-pub fn create_access_token(secret: &str) -> String {
-    let claims = TokenClaims {
-        sub: "access_token".to_string(),
-        iat: chrono::Utc::now().timestamp(),
-        exp: chrono::Utc::now().timestamp() + 3600, // 1 hour
-    };
+pub fn create_access_token(user_id: Uuid, secret: &str) -> String {
+    let claims = TokenClaims::new(user_id, TokenType::Access, 3600); // 1 hour
     create_token(claims, secret)
 }
 
-pub fn create_refresh_token(secret: &str) -> String {
-    let claims = TokenClaims {
-        sub: "refresh_token".to_string(),
-        iat: chrono::Utc::now().timestamp(),
-        exp: chrono::Utc::now().timestamp() + 604800, // 7 days
-    };
+pub fn create_refresh_token(user_id: Uuid, secret: &str) -> String {
+    let claims = TokenClaims::new(user_id, TokenType::Refresh, 604800); // 7 days
     create_token(claims, secret)
 }
 
-pub fn exchange_refresh_token(refresh_token: &str, secret: &str) -> Result<String, jsonwebtoken::errors::Error> {
-    let claims = validate_token(refresh_token, secret)?;
-    if claims.sub == "refresh_token" {
-        Ok(create_refresh_token(secret))
-    } else {
-        Err(jsonwebtoken::errors::Error::from(jsonwebtoken::errors::ErrorKind::InvalidToken))
-    }
+/// Short-lived token passed through a broker's OAuth `state` parameter so the
+/// public callback can tell which user started the flow.
+pub fn create_oauth_state_token(user_id: Uuid, secret: &str) -> String {
+    let claims = TokenClaims::new(user_id, TokenType::OauthState, 600); // 10 minutes
+    create_token(claims, secret)
+}
+
+/// Validates `refresh_token` and returns the user it was issued to along with a rotated refresh token.
+pub fn exchange_refresh_token(refresh_token: &str, secret: &str) -> Result<(Uuid, String), jsonwebtoken::errors::Error> {
+    let claims = validate_token(refresh_token, secret, TokenType::Refresh)?;
+    Ok((claims.sub, create_refresh_token(claims.sub, secret)))
 }
 
 fn get_token<B>(name: &str, cookie_jar: &CookieJar, req: &Request<B>) -> Option<String> {
     cookie_jar.get(name).map(|cookie| cookie.value().to_string()).or_else(|| {
-        req.headers().get(header::AUTHORIZATION).and_then(|auth_header| auth_header.to_str().ok()).and_then(
-            |auth_value| {
-                if auth_value.starts_with("Bearer ") {
-                    Some(auth_value[7..].to_owned())
-                } else {
-                    None
-                }
-            },
-        )
+        req.headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|auth_header| auth_header.to_str().ok())
+            .and_then(|auth_value| auth_value.strip_prefix("Bearer ").map(|token| token.to_owned()))
     })
 }
 
-pub async fn auth<B>(cookie_jar: CookieJar, State(state): State<AppState>, req: Request<B>, next: Next<B>) -> impl IntoResponse {
+pub async fn auth<B>(cookie_jar: CookieJar, State(state): State<AppState>, mut req: Request<B>, next: Next<B>) -> impl IntoResponse {
     let token = match get_token("access_token", &cookie_jar, &req) {
         Some(token) => token,
         None => return StatusCode::IM_A_TEAPOT.into_response(),
     };
-    match validate_token(&token, &state.env.jwt_access_token_secret) {
+    let claims = match validate_token(&token, &state.env.jwt_access_token_secret, TokenType::Access) {
         Ok(claims) => claims,
         Err(_) => return StatusCode::IM_A_TEAPOT.into_response(),
     };
+    req.extensions_mut().insert(AuthUser {
+        user_id: claims.sub,
+        token_id: claims.jti,
+    });
     next.run(req).await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{create_access_token, create_refresh_token, exchange_refresh_token, validate_token, TokenType};
+    use uuid::Uuid;
+
+    const SECRET: &str = "secret";
+
+    #[test]
+    fn test_access_token_carries_user_id() {
+        let user_id = Uuid::new_v4();
+        let token = create_access_token(user_id, SECRET);
+        let claims = validate_token(&token, SECRET, TokenType::Access).unwrap();
+        assert_eq!(claims.sub, user_id);
+        assert_eq!(claims.iss, "tradetracker");
+        assert_eq!(claims.aud, "tradetracker-api");
+    }
+
+    #[test]
+    fn test_token_type_is_enforced() {
+        let user_id = Uuid::new_v4();
+        let refresh_token = create_refresh_token(user_id, SECRET);
+        assert!(validate_token(&refresh_token, SECRET, TokenType::Access).is_err());
+        let access_token = create_access_token(user_id, SECRET);
+        assert!(exchange_refresh_token(&access_token, SECRET).is_err());
+    }
+
+    #[test]
+    fn test_exchange_refresh_token_rotates_token() {
+        let user_id = Uuid::new_v4();
+        let refresh_token = create_refresh_token(user_id, SECRET);
+        let (exchanged_user_id, rotated_refresh_token) = exchange_refresh_token(&refresh_token, SECRET).unwrap();
+        assert_eq!(exchanged_user_id, user_id);
+        let old_claims = validate_token(&refresh_token, SECRET, TokenType::Refresh).unwrap();
+        let new_claims = validate_token(&rotated_refresh_token, SECRET, TokenType::Refresh).unwrap();
+        assert_ne!(old_claims.jti, new_claims.jti);
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     handlers::{
-        self,
+        self, journal,
         providers::{tda, tradetracker},
     },
     middleware, AppState,
@@ -26,7 +26,35 @@ impl Router {
     pub fn new(app_state: AppState) -> Self {
         let private_routes = axum::Router::<AppState>::new()
             .route("/get_accounts", get(tda::get_accounts))
+            .route("/account_display_names", get(tda::get_account_display_names))
+            .route("/:account_id/get_account", get(tda::get_account))
             .route("/:account_id/get_orders", get(tda::get_orders))
+            .route("/:account_id/orders", post(tda::place_order))
+            .route("/:account_id/orders/:order_id", get(tda::get_order).put(tda::replace_order).delete(tda::cancel_order))
+            .route("/orders", get(tda::get_all_orders))
+            .route("/:account_id/saved_orders", get(tda::get_saved_orders).post(tda::create_saved_order))
+            .route(
+                "/:account_id/saved_orders/:saved_order_id",
+                get(tda::get_saved_order).put(tda::replace_saved_order).delete(tda::delete_saved_order),
+            )
+            .route("/:account_id/transactions", get(tda::get_transactions))
+            .route("/:account_id/transactions/:transaction_id", get(tda::get_transaction))
+            .route("/watchlists", get(tda::get_watchlists))
+            .route("/:account_id/watchlists", get(tda::get_account_watchlists).post(tda::create_watchlist))
+            .route(
+                "/:account_id/watchlists/:watchlist_id",
+                get(tda::get_watchlist).put(tda::replace_watchlist).patch(tda::update_watchlist).delete(tda::delete_watchlist),
+            )
+            .route("/quotes", get(tda::get_quotes))
+            .route("/price_history/:symbol", get(tda::get_price_history))
+            .route("/option_chains/:symbol", get(tda::get_option_chain))
+            .route("/movers/:index", get(tda::get_movers))
+            .route("/instruments", get(tda::search_instruments))
+            .route("/instruments/:cusip", get(tda::get_instrument))
+            .route("/journal/sync", post(journal::sync_journal))
+            .route("/journal/trades", get(journal::get_trades))
+            .route("/journal/trades/:trade_id", get(journal::get_trade).patch(journal::annotate_trade))
+            .route("/stream", get(tda::stream))
             .route("/auth/providers/tda", get(tda::auth::get_authorization_url))
             .route("/auth/providers/tda", post(tda::auth_tda_refresh_token))
             .route_layer(axum::middleware::from_fn_with_state(app_state.clone(), middleware::jwt::auth));
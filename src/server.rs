@@ -4,6 +4,12 @@ use std::net::SocketAddr;
 
 pub struct Server {}
 
+impl Default for Server {
+    fn default() -> Self {
+        Server::new()
+    }
+}
+
 impl Server {
     pub async fn start(&self, router: Router) {
         let addr = SocketAddr::from(([0, 0, 0, 0], 3000));
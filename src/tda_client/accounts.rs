@@ -1,14 +1,17 @@
-use super::TDAmeritradeClient;
+use super::{error::TdaError, TDAmeritradeClient};
 use async_trait::async_trait;
-use log::error;
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
-use std::fmt::{Display, Formatter, Result};
+use std::fmt::{self, Display, Formatter};
 
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(untagged)]
+#[serde(tag = "type")]
 pub enum SecuritiesAccount {
+    #[serde(rename = "CASH")]
     CashAccount(CashAccount),
+    #[serde(rename = "MARGIN")]
+    MarginAccount(MarginAccount),
 }
 
 impl Default for SecuritiesAccount {
@@ -17,21 +20,39 @@ impl Default for SecuritiesAccount {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+impl SecuritiesAccount {
+    pub fn account_id(&self) -> &str {
+        match self {
+            SecuritiesAccount::CashAccount(account) => &account.account_id,
+            SecuritiesAccount::MarginAccount(account) => &account.account_id,
+        }
+    }
+
+    pub fn account_type(&self) -> SecuritiesAccountType {
+        match self {
+            SecuritiesAccount::CashAccount(_) => SecuritiesAccountType::Cash,
+            SecuritiesAccount::MarginAccount(_) => SecuritiesAccountType::Margin,
+        }
+    }
+
+    pub fn positions(&self) -> &[Position] {
+        match self {
+            SecuritiesAccount::CashAccount(account) => &account.positions,
+            SecuritiesAccount::MarginAccount(account) => &account.positions,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum SecuritiesAccountType {
+    #[default]
     Cash,
     Margin,
 }
 
-impl Default for SecuritiesAccountType {
-    fn default() -> Self {
-        SecuritiesAccountType::Cash
-    }
-}
-
 impl Display for SecuritiesAccountType {
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
             SecuritiesAccountType::Cash => write!(f, "CASH"),
             SecuritiesAccountType::Margin => write!(f, "MARGIN"),
@@ -55,8 +76,10 @@ pub struct CashAccount {
     pub is_day_trader: bool,
     pub projected_balances: ProjectedBalances,
     pub round_trips: u64,
-    #[serde(rename = "type")]
-    pub type_field: SecuritiesAccountType,
+    #[serde(default)]
+    pub positions: Vec<Position>,
+    #[serde(default)]
+    pub order_strategies: Vec<OrderGet>,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -114,18 +137,171 @@ pub struct ProjectedBalances {
     pub cash_available_for_withdrawal: f64,
 }
 
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarginAccount {
+    pub account_id: String,
+    pub current_balances: MarginCurrentBalances,
+    pub initial_balances: MarginInitialBalances,
+    pub is_closing_only_restricted: bool,
+    pub is_day_trader: bool,
+    pub projected_balances: MarginProjectedBalances,
+    pub round_trips: u64,
+    #[serde(default)]
+    pub positions: Vec<Position>,
+    #[serde(default)]
+    pub order_strategies: Vec<OrderGet>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct MarginCurrentBalances {
+    pub accrued_interest: f64,
+    pub available_funds: f64,
+    pub available_funds_non_marginable_trade: f64,
+    pub bond_value: f64,
+    pub buying_power: f64,
+    pub buying_power_non_marginable_trade: f64,
+    pub cash_balance: f64,
+    pub cash_receipts: f64,
+    pub day_trading_buying_power: f64,
+    pub day_trading_buying_power_call: f64,
+    pub equity: f64,
+    pub equity_percentage: f64,
+    pub is_in_call: bool,
+    pub liquidation_value: f64,
+    pub long_margin_value: f64,
+    pub long_market_value: f64,
+    pub long_option_market_value: f64,
+    pub maintenance_call: f64,
+    pub maintenance_requirement: f64,
+    pub margin_balance: f64,
+    pub money_market_fund: f64,
+    pub mutual_fund_value: f64,
+    pub option_buying_power: f64,
+    pub pending_deposits: f64,
+    pub reg_t_call: f64,
+    pub savings: f64,
+    pub short_balance: f64,
+    pub short_margin_value: f64,
+    pub short_market_value: f64,
+    pub short_option_market_value: f64,
+    pub sma: f64,
+    pub stock_buying_power: f64,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct MarginInitialBalances {
+    pub account_value: f64,
+    pub accrued_interest: f64,
+    pub available_funds_non_marginable_trade: f64,
+    pub bond_value: f64,
+    pub buying_power: f64,
+    pub cash_available_for_trading: f64,
+    pub cash_balance: f64,
+    pub cash_receipts: f64,
+    pub day_trading_buying_power: f64,
+    pub day_trading_buying_power_call: f64,
+    pub day_trading_equity_call: f64,
+    pub equity: f64,
+    pub equity_percentage: f64,
+    pub is_in_call: bool,
+    pub liquidation_value: f64,
+    pub long_margin_value: f64,
+    pub long_option_market_value: f64,
+    pub long_stock_value: f64,
+    pub maintenance_call: f64,
+    pub maintenance_requirement: f64,
+    pub margin: f64,
+    pub margin_balance: f64,
+    pub margin_equity: f64,
+    pub money_market_fund: f64,
+    pub mutual_fund_value: f64,
+    pub pending_deposits: f64,
+    pub reg_t_call: f64,
+    pub short_balance: f64,
+    pub short_margin_value: f64,
+    pub short_option_market_value: f64,
+    pub short_stock_value: f64,
+    pub total_cash: f64,
+    pub unsettled_cash: f64,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct MarginProjectedBalances {
+    pub available_funds: f64,
+    pub available_funds_non_marginable_trade: f64,
+    pub buying_power: f64,
+    pub day_trading_buying_power: f64,
+    pub day_trading_buying_power_call: f64,
+    pub is_in_call: bool,
+    pub maintenance_call: f64,
+    pub option_buying_power: f64,
+    pub reg_t_call: f64,
+    pub stock_buying_power: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Position {
+    pub short_quantity: f64,
+    pub average_price: f64,
+    pub current_day_profit_loss: f64,
+    pub current_day_profit_loss_percentage: f64,
+    pub long_quantity: f64,
+    #[serde(default)]
+    pub settled_long_quantity: f64,
+    #[serde(default)]
+    pub settled_short_quantity: f64,
+    #[serde(default)]
+    pub aged_quantity: f64,
+    pub instrument: Instrument,
+    pub market_value: f64,
+    #[serde(default)]
+    pub maintenance_requirement: f64,
+    #[serde(default)]
+    pub current_day_cost: f64,
+    #[serde(default)]
+    pub previous_session_long_quantity: f64,
+}
+
 #[async_trait]
 pub trait TDAmeritradeClientAccounts {
-    async fn get_accounts(&self, token: &str) -> Vec<GetAccountsResponse>;
-    async fn get_orders(&self, token: &str, account_id: &str) -> Vec<Order>;
+    async fn get_accounts(&self, token: &str) -> Result<Vec<GetAccountsResponse>, TdaError>;
+    async fn get_account(&self, token: &str, account_id: &str) -> Result<GetAccountsResponse, TdaError>;
+    async fn get_orders(&self, token: &str, account_id: &str, query: &GetOrdersQuery) -> Result<Vec<Order>, TdaError>;
+    async fn get_orders_for_all_accounts(&self, token: &str, query: &GetOrdersQuery) -> Result<Vec<Order>, TdaError>;
+    async fn get_order(&self, token: &str, account_id: &str, order_id: &str) -> Result<Order, TdaError>;
 }
 
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
-pub struct TDAmeritradeClientError {
-    pub error: String,
+/// Without dates TDA only returns orders entered in the last few days; ranges can reach
+/// back 60 days and need both ends.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetOrdersQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_results: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_entered_time: Option<NaiveDate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to_entered_time: Option<NaiveDate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<Status>,
+}
+
+impl GetOrdersQuery {
+    /// TDA rejects a range with only one end, so handlers refuse it before calling TDA.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.from_entered_time.is_some() != self.to_entered_time.is_some() {
+            return Err("fromEnteredTime and toEnteredTime must be given together".to_string());
+        }
+        Ok(())
+    }
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum Session {
     Normal,
@@ -134,7 +310,7 @@ pub enum Session {
     Seamless,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum Duration {
     Day,
@@ -142,7 +318,7 @@ pub enum Duration {
     FillOrKill,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum OrderType {
     Market,
@@ -158,14 +334,7 @@ pub enum OrderType {
     NetZero,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct OrderGetCancelTime {
-    pub date: String,
-    pub short_format: bool,
-}
-
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum ComplexOrderStrategyType {
     None,
@@ -190,7 +359,7 @@ pub enum ComplexOrderStrategyType {
     Custom,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum RequestedDestination {
     Inet,
@@ -207,7 +376,7 @@ pub enum RequestedDestination {
     Auto,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum PriceLinkBasis {
     Manual,
@@ -221,7 +390,7 @@ pub enum PriceLinkBasis {
     Average,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum PriceLinkType {
     Value,
@@ -229,7 +398,7 @@ pub enum PriceLinkType {
     Tick,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum StopType {
     Standard,
@@ -239,7 +408,7 @@ pub enum StopType {
     Mark,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum TaxLotMethod {
     Fifo,
@@ -250,7 +419,7 @@ pub enum TaxLotMethod {
     SpecificLot,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum OrderLegType {
     Equity,
@@ -262,7 +431,7 @@ pub enum OrderLegType {
     Currency,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum AssetType {
     Equity,
@@ -274,28 +443,33 @@ pub enum AssetType {
     Currency,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Equity {
-    pub asset_type: AssetType,
-    pub cusip: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cusip: Option<String>,
     pub symbol: String,
-    pub description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FixedIncome {
-    pub asset_type: AssetType,
-    pub cusip: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cusip: Option<String>,
     pub symbol: String,
-    pub description: String,
-    pub maturity_date: String,
-    pub variable_rate: f64,
-    pub factor: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub maturity_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variable_rate: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub factor: Option<f64>,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum MutualFundType {
     NotApplicable,
@@ -305,34 +479,58 @@ pub enum MutualFundType {
     NoLoadTaxable,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MutualFund {
-    pub asset_type: AssetType,
-    pub cusip: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cusip: Option<String>,
+    pub symbol: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r#type: Option<MutualFundType>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Index {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cusip: Option<String>,
+    pub symbol: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Currency {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cusip: Option<String>,
     pub symbol: String,
-    pub description: String,
-    pub r#type: MutualFundType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum CashEquivalentType {
     Savings,
     MoneyMarketFund,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CashEquivalent {
-    pub asset_type: AssetType,
-    pub cusip: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cusip: Option<String>,
     pub symbol: String,
-    pub description: String,
-    pub r#type: CashEquivalentType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r#type: Option<CashEquivalentType>,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum OptionType {
     Vanilla,
@@ -340,14 +538,14 @@ pub enum OptionType {
     Barrier,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum PutCall {
     Put,
     Call,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum CurrencyType {
     Usd,
@@ -356,7 +554,7 @@ pub enum CurrencyType {
     Jpy,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OptionDeliverable {
     pub symbol: String,
@@ -365,30 +563,86 @@ pub struct OptionDeliverable {
     pub asset_type: AssetType,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
-pub struct Option {
-    pub asset_type: AssetType,
-    pub cusip: String,
+pub struct OptionInstrument {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cusip: Option<String>,
     pub symbol: String,
-    pub description: String,
-    pub r#type: OptionType,
-    pub put_call: PutCall,
-    pub underlying_symbol: String,
-    pub option_multiplier: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r#type: Option<OptionType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub put_call: Option<PutCall>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub underlying_symbol: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub option_multiplier: Option<f64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub option_deliverables: Vec<OptionDeliverable>,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "assetType", rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum Instrument {
     Equity(Equity),
     FixedIncome(FixedIncome),
     MutualFund(MutualFund),
     CashEquivalent(CashEquivalent),
-    Option(Option),
+    Option(OptionInstrument),
+    Index(Index),
+    Currency(Currency),
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+impl Instrument {
+    pub fn equity(symbol: &str) -> Self {
+        Instrument::Equity(Equity {
+            cusip: None,
+            symbol: symbol.to_string(),
+            description: None,
+        })
+    }
+
+    pub fn option(symbol: &str) -> Self {
+        Instrument::Option(OptionInstrument {
+            cusip: None,
+            symbol: symbol.to_string(),
+            description: None,
+            r#type: None,
+            put_call: None,
+            underlying_symbol: None,
+            option_multiplier: None,
+            option_deliverables: vec![],
+        })
+    }
+
+    pub fn symbol(&self) -> &str {
+        match self {
+            Instrument::Equity(instrument) => &instrument.symbol,
+            Instrument::FixedIncome(instrument) => &instrument.symbol,
+            Instrument::MutualFund(instrument) => &instrument.symbol,
+            Instrument::CashEquivalent(instrument) => &instrument.symbol,
+            Instrument::Option(instrument) => &instrument.symbol,
+            Instrument::Index(instrument) => &instrument.symbol,
+            Instrument::Currency(instrument) => &instrument.symbol,
+        }
+    }
+
+    pub fn asset_type(&self) -> AssetType {
+        match self {
+            Instrument::Equity(_) => AssetType::Equity,
+            Instrument::FixedIncome(_) => AssetType::FixedIncome,
+            Instrument::MutualFund(_) => AssetType::MutualFund,
+            Instrument::CashEquivalent(_) => AssetType::CashEquivalent,
+            Instrument::Option(_) => AssetType::Option,
+            Instrument::Index(_) => AssetType::Index,
+            Instrument::Currency(_) => AssetType::Currency,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum Instruction {
     Buy,
@@ -402,15 +656,15 @@ pub enum Instruction {
     Exchange,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum PositionEffect {
-    Open,
-    Close,
+    Opening,
+    Closing,
     Automatic,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum QuantityType {
     AllShares,
@@ -418,19 +672,37 @@ pub enum QuantityType {
     Shares,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OrderLeg {
-    pub order_leg_type: OrderLegType,
-    pub leg_id: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order_leg_type: Option<OrderLegType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub leg_id: Option<i64>,
     pub instrument: Instrument,
     pub instruction: Instruction,
-    pub position_effect: PositionEffect,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position_effect: Option<PositionEffect>,
     pub quantity: f64,
-    pub quantity_type: QuantityType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quantity_type: Option<QuantityType>,
+}
+
+impl OrderLeg {
+    pub fn new(instruction: Instruction, quantity: f64, instrument: Instrument) -> Self {
+        Self {
+            order_leg_type: None,
+            leg_id: None,
+            instrument,
+            instruction,
+            position_effect: None,
+            quantity,
+            quantity_type: None,
+        }
+    }
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum SpecialInstruction {
     AllOrNone,
@@ -438,7 +710,7 @@ pub enum SpecialInstruction {
     AllOrNoneDoNotReduce,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum OrderStrategyType {
     Single,
@@ -446,7 +718,7 @@ pub enum OrderStrategyType {
     Trigger,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum Status {
     AwaitingParentOrder,
@@ -466,135 +738,295 @@ pub enum Status {
     Expired,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-pub enum ActivityType {
-    Execution,
-    OrderAction,
-}
-
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum ExecutionType {
     Fill,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ExecutionLeg {
     pub leg_id: i64,
     pub quantity: f64,
+    #[serde(default)]
     pub mismarked_quantity: f64,
     pub price: f64,
     pub time: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instrument_id: Option<i64>,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Execution {
-    pub activity_type: ActivityType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub activity_id: Option<i64>,
     pub execution_type: ExecutionType,
     pub quantity: f64,
+    #[serde(default)]
     pub order_remaining_quantity: f64,
+    #[serde(default)]
     pub execution_legs: Vec<ExecutionLeg>,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "activityType", rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum OrderActivity {
     Execution(Execution),
+    OrderAction,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+/// An order as TDA reports it. Only the fields every order carries are required: OCO
+/// parents, for one, have no order type, price or legs of their own, just
+/// `child_order_strategies`.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OrderGet {
-    pub session: Session,
-    pub duration: Duration,
-    pub order_type: OrderType,
-    pub cancel_time: OrderGetCancelTime,
-    pub complex_order_strategy_type: ComplexOrderStrategyType,
-    pub quantity: f64,
-    pub filled_quantity: f64,
-    pub remaining_quantity: f64,
-    pub requested_destination: RequestedDestination,
-    pub destination_link_name: String,
-    pub release_time: String,
-    pub stop_price: f64,
-    pub stop_price_link_basis: PriceLinkBasis,
-    pub stop_price_link_type: PriceLinkType,
-    pub stop_price_offset: f64,
-    pub stop_type: StopType,
-    pub price_link_basis: PriceLinkBasis,
-    pub price_link_type: PriceLinkType,
-    pub price: f64,
-    pub tax_lot_method: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session: Option<Session>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration: Option<Duration>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order_type: Option<OrderType>,
+    /// Expiry date of good-till-cancel orders, e.g. `2023-08-28`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cancel_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub complex_order_strategy_type: Option<ComplexOrderStrategyType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quantity: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filled_quantity: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remaining_quantity: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub requested_destination: Option<RequestedDestination>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destination_link_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub release_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_price: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_price_link_basis: Option<PriceLinkBasis>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_price_link_type: Option<PriceLinkType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_price_offset: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_type: Option<StopType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price_link_basis: Option<PriceLinkBasis>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price_link_type: Option<PriceLinkType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tax_lot_method: Option<TaxLotMethod>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub order_leg_collection: Vec<OrderLeg>,
-    pub activation_price: f64,
-    pub special_instruction: SpecialInstruction,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub activation_price: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub special_instruction: Option<SpecialInstruction>,
     pub order_strategy_type: OrderStrategyType,
     pub order_id: i64,
+    #[serde(default)]
     pub cancelable: bool,
+    #[serde(default)]
     pub editable: bool,
     pub status: Status,
-    pub entered_time: String,
-    pub close_time: String,
-    pub tag: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entered_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub close_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
     pub account_id: i64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub order_activity_collection: Vec<OrderActivity>,
-    pub replacing_order_collection: Vec<Value>, // TODO: What is this?
-    pub child_order_strategy_type: Vec<Value>,  // TODO: What is this?
-    pub status_description: String,
+    /// The orders that replaced this one, when its status is `REPLACED`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub replacing_order_collection: Vec<OrderGet>,
+    /// The legs of an OCO order, or the orders a TRIGGER order sends once it fills.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub child_order_strategies: Vec<OrderGet>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_description: Option<String>,
+}
+
+impl OrderGet {
+    /// This order followed by every order nested in it, depth first.
+    pub fn flatten(&self) -> Vec<&OrderGet> {
+        let mut orders = vec![self];
+        for nested in self.child_order_strategies.iter().chain(&self.replacing_order_collection) {
+            orders.extend(nested.flatten());
+        }
+        orders
+    }
+
+    pub fn executions(&self) -> impl Iterator<Item = &Execution> {
+        self.order_activity_collection.iter().filter_map(|activity| match activity {
+            OrderActivity::Execution(execution) => Some(execution),
+            OrderActivity::OrderAction => None,
+        })
+    }
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
 pub enum Order {
     OrderGet(OrderGet),
 }
 
 #[async_trait]
 impl TDAmeritradeClientAccounts for TDAmeritradeClient {
-    async fn get_accounts(&self, token: &str) -> Vec<GetAccountsResponse> {
+    async fn get_accounts(&self, token: &str) -> Result<Vec<GetAccountsResponse>, TdaError> {
         let url = format!("{}/accounts", self.base_url);
-        let request = self.client.get(&url).bearer_auth(token).send().await;
-        let body = match request {
-            Ok(data) => match data.json::<Vec<GetAccountsResponse>>().await {
-                Ok(json) => json,
-                Err(e) => {
-                    error!("get_accounts json error: {}", e);
-                    vec![]
-                }
-            },
-            Err(e) => {
-                error!("get_accounts request error: {}", e);
-                vec![]
-            }
-        };
-        body
+        self.send_json::<Vec<GetAccountsResponse>>(self.client.get(&url).bearer_auth(token)).await
+    }
+    async fn get_account(&self, token: &str, account_id: &str) -> Result<GetAccountsResponse, TdaError> {
+        let url = format!("{}/accounts/{}", self.base_url, account_id);
+        let request = self.client.get(&url).query(&[("fields", "positions,orders")]).bearer_auth(token);
+        self.send_json::<GetAccountsResponse>(request).await
     }
-    async fn get_orders(&self, token: &str, account_id: &str) -> Vec<Order> {
+    async fn get_orders(&self, token: &str, account_id: &str, query: &GetOrdersQuery) -> Result<Vec<Order>, TdaError> {
         let url = format!("{}/accounts/{}/orders", self.base_url, account_id);
-        let request = self.client.get(&url).bearer_auth(&token).send().await;
-        let body = match request {
-            Ok(data) => match data.text().await {
-                Ok(text) => {
-                    let json = serde_json::from_str::<Vec<OrderGet>>(&text);
-                    match json {
-                        Ok(json) => json.into_iter().map(Order::OrderGet).collect(),
-                        Err(e) => {
-                            error!("get_orders json error: {}", e);
-                            vec![]
-                        }
-                    }
-                }
-                Err(e) => {
-                    error!("get_orders text error: {}", e);
-                    vec![]
-                }
-            },
-            Err(e) => {
-                error!("get_orders request error: {}", e);
-                vec![]
-            }
+        let orders = self.send_json::<Vec<OrderGet>>(self.client.get(&url).query(query).bearer_auth(token)).await?;
+        Ok(orders.into_iter().map(Order::OrderGet).collect())
+    }
+    async fn get_orders_for_all_accounts(&self, token: &str, query: &GetOrdersQuery) -> Result<Vec<Order>, TdaError> {
+        let url = format!("{}/orders", self.base_url);
+        let orders = self.send_json::<Vec<OrderGet>>(self.client.get(&url).query(query).bearer_auth(token)).await?;
+        Ok(orders.into_iter().map(Order::OrderGet).collect())
+    }
+    async fn get_order(&self, token: &str, account_id: &str, order_id: &str) -> Result<Order, TdaError> {
+        let url = format!("{}/accounts/{}/orders/{}", self.base_url, account_id, order_id);
+        let order = self.send_json::<OrderGet>(self.client.get(&url).bearer_auth(token)).await?;
+        Ok(Order::OrderGet(order))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AssetType, GetAccountsResponse, GetOrdersQuery, Instrument, OrderGet, OrderStrategyType, PositionEffect, SecuritiesAccount, SecuritiesAccountType, Status};
+    use chrono::NaiveDate;
+    use serde_json::Value;
+
+    const ACCOUNTS: &str = include_str!("fixtures/accounts.json");
+    const ORDERS: &str = include_str!("fixtures/orders.json");
+
+    #[test]
+    fn test_deserialize_cash_and_margin_accounts() {
+        let accounts = serde_json::from_str::<Vec<GetAccountsResponse>>(ACCOUNTS).unwrap();
+        assert_eq!(accounts.len(), 2);
+
+        let cash = &accounts[0].securities_account;
+        assert_eq!(cash.account_type(), SecuritiesAccountType::Cash);
+        assert_eq!(cash.account_id(), "123456789");
+        assert_eq!(cash.positions().len(), 1);
+        match &cash.positions()[0].instrument {
+            Instrument::Equity(equity) => assert_eq!(equity.symbol, "AAPL"),
+            instrument => panic!("unexpected instrument: {:?}", instrument),
+        }
+
+        let margin = match &accounts[1].securities_account {
+            SecuritiesAccount::MarginAccount(margin) => margin,
+            account => panic!("unexpected account: {:?}", account),
+        };
+        assert_eq!(margin.current_balances.buying_power, 5000.0);
+        assert_eq!(margin.current_balances.sma, 2500.0);
+        assert_eq!(margin.initial_balances.margin_equity, 2500.0);
+        assert_eq!(margin.projected_balances.day_trading_buying_power, 10000.0);
+        let asset_types: Vec<AssetType> = margin.positions.iter().map(|position| position.instrument.asset_type()).collect();
+        assert_eq!(asset_types, vec![AssetType::Index, AssetType::Currency]);
+        assert_eq!(margin.positions[0].instrument.symbol(), "$SPX.X");
+    }
+
+    #[test]
+    fn test_deserialize_account_positions_and_balances() {
+        // `get_account` asks for `fields=positions,orders`, which adds both lists to the account.
+        let mut account = serde_json::from_str::<Vec<Value>>(ACCOUNTS).unwrap().remove(0);
+        let orders = serde_json::from_str::<Vec<Value>>(ORDERS).unwrap();
+        account["securitiesAccount"]["orderStrategies"] = Value::Array(orders[..2].to_vec());
+        let account = serde_json::from_value::<GetAccountsResponse>(account).unwrap();
+
+        let cash = match &account.securities_account {
+            SecuritiesAccount::CashAccount(cash) => cash,
+            account => panic!("unexpected account: {:?}", account),
         };
-        body
+        let position = &cash.positions[0];
+        assert_eq!(position.instrument.symbol(), "AAPL");
+        assert_eq!((position.long_quantity, position.short_quantity), (10.0, 0.0));
+        assert_eq!(position.average_price, 142.5);
+        assert_eq!(position.market_value, 1437.0);
+        assert_eq!(position.current_day_profit_loss, 12.0);
+        assert_eq!(cash.current_balances.liquidation_value, 1937.0);
+        assert_eq!(cash.current_balances.cash_balance, 500.0);
+        let order_ids: Vec<i64> = cash.order_strategies.iter().map(|order| order.order_id).collect();
+        assert_eq!(order_ids, vec![1001, 1002]);
+    }
+
+    #[test]
+    fn test_deserialize_orders() {
+        let orders = serde_json::from_str::<Vec<OrderGet>>(ORDERS).unwrap();
+        assert_eq!(orders.len(), 6);
+        let filled = &orders[0];
+        assert_eq!(filled.order_leg_collection[0].position_effect, Some(PositionEffect::Opening));
+        let execution = filled.executions().next().unwrap();
+        assert_eq!(execution.execution_legs[0].price, 142.5);
+
+        let oco = &orders[2];
+        assert_eq!(oco.order_strategy_type, OrderStrategyType::Oco);
+        assert_eq!(oco.order_type, None);
+        assert!(oco.order_leg_collection.is_empty());
+        let children: Vec<i64> = oco.child_order_strategies.iter().map(|child| child.order_id).collect();
+        assert_eq!(children, vec![1006, 1007]);
+
+        let replaced = &orders[3];
+        assert_eq!(replaced.status, Status::Replaced);
+        assert_eq!(replaced.replacing_order_collection[0].status, Status::Filled);
+
+        let trigger = &orders[5];
+        assert_eq!(trigger.order_strategy_type, OrderStrategyType::Trigger);
+        assert!(matches!(&trigger.order_leg_collection[0].instrument, Instrument::Option(option) if option.underlying_symbol.as_deref() == Some("AAPL")));
+        let flattened: Vec<i64> = orders.iter().flat_map(OrderGet::flatten).map(|order| order.order_id).collect();
+        assert_eq!(flattened, vec![1001, 1002, 1005, 1006, 1007, 1010, 1011, 1012, 1008, 1009]);
+        let fills = orders.iter().flat_map(OrderGet::flatten).filter(|order| order.executions().next().is_some()).count();
+        assert_eq!(fills, 3);
+    }
+
+    #[test]
+    fn test_orders_round_trip() {
+        let orders = serde_json::from_str::<Vec<OrderGet>>(ORDERS).unwrap();
+        // Every field TDA sent survives, so handlers pass orders through unchanged.
+        assert_eq!(serde_json::to_value(&orders).unwrap(), serde_json::from_str::<Value>(ORDERS).unwrap());
+    }
+
+    #[test]
+    fn test_serialize_get_orders_query() {
+        let query = GetOrdersQuery {
+            max_results: Some(50),
+            from_entered_time: NaiveDate::from_ymd_opt(2023, 2, 1),
+            to_entered_time: NaiveDate::from_ymd_opt(2023, 3, 1),
+            status: Some(Status::Filled),
+        };
+        let request = reqwest::Client::new().get("http://localhost/orders").query(&query).build().unwrap();
+        assert_eq!(request.url().query(), Some("maxResults=50&fromEnteredTime=2023-02-01&toEnteredTime=2023-03-01&status=FILLED"));
+        let request = reqwest::Client::new().get("http://localhost/orders").query(&GetOrdersQuery::default()).build().unwrap();
+        assert_eq!(request.url().query(), None);
+    }
+
+    #[test]
+    fn test_accounts_round_trip() {
+        let accounts = serde_json::from_str::<Vec<GetAccountsResponse>>(ACCOUNTS).unwrap();
+        let json = serde_json::to_string(&accounts).unwrap();
+        assert!(json.contains(r#""type":"CASH""#));
+        assert!(json.contains(r#""type":"MARGIN""#));
+        let round_tripped = serde_json::from_str::<Vec<GetAccountsResponse>>(&json).unwrap();
+        assert_eq!(accounts, round_tripped);
+        assert!(json.contains(r#""assetType":"INDEX""#));
+        assert!(json.contains(r#""assetType":"CURRENCY""#));
     }
 }
@@ -1,8 +1,6 @@
-use super::TDAmeritradeClient;
+use super::{error::TdaError, TDAmeritradeClient};
 use async_trait::async_trait;
-use log::error;
 use serde::{Deserialize, Serialize};
-use std::env;
 use url::form_urlencoded;
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
@@ -11,6 +9,7 @@ pub struct TokenResponse {
     pub token_type: Option<String>,
     pub expires_in: Option<u64>,
     pub refresh_token: Option<String>,
+    pub refresh_token_expires_in: Option<u64>,
     pub scope: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
@@ -19,17 +18,16 @@ pub struct TokenResponse {
 #[async_trait]
 pub trait TDAmeritradeClientAuth {
     fn get_authorization_url(&self) -> String;
-    async fn exchange_authorization_code_for_token(&self, code: &str) -> Result<TokenResponse, reqwest::Error>;
-    async fn exchange_refresh_token_for_token(&self, refresh_token: &str) -> Result<TokenResponse, reqwest::Error>;
+    async fn exchange_authorization_code_for_token(&self, code: &str) -> Result<TokenResponse, TdaError>;
+    async fn exchange_refresh_token_for_token(&self, refresh_token: &str) -> Result<TokenResponse, TdaError>;
 }
 
 #[async_trait]
 impl TDAmeritradeClientAuth for TDAmeritradeClient {
     fn get_authorization_url(&self) -> String {
         let client_id = &self.api_key;
-        let callback_url = env::var("TDA_API_CALLBACK_URL").expect("TDA_API_CALLBACK_URL not found in .env");
-        let redirect_uri = form_urlencoded::byte_serialize(callback_url.as_bytes()).collect::<String>();
-        let base_url = "https://auth.tdameritrade.com/auth";
+        let redirect_uri = form_urlencoded::byte_serialize(self.callback_url.as_bytes()).collect::<String>();
+        let base_url = &self.auth_url;
         let response_type = "code";
         let scope = "AccountAccess";
 
@@ -39,51 +37,39 @@ impl TDAmeritradeClientAuth for TDAmeritradeClient {
         )
     }
 
-    async fn exchange_authorization_code_for_token(&self, code: &str) -> Result<TokenResponse, reqwest::Error> {
+    async fn exchange_authorization_code_for_token(&self, code: &str) -> Result<TokenResponse, TdaError> {
         let url = format!("{}/oauth2/token", self.base_url);
-        let redirect_uri = env::var("TDA_API_CALLBACK_URL").expect("TDA_API_CALLBACK_URL not found in .env");
         let params = [
             ("grant_type", "authorization_code"),
             ("access_type", "offline"),
             ("code", code),
             ("client_id", &self.api_key),
-            ("redirect_uri", redirect_uri.as_str()),
+            ("redirect_uri", self.callback_url.as_str()),
         ];
 
-        let res = self.client.post(&url).form(&params).send().await;
-        match res {
-            Ok(data) => data.json::<TokenResponse>().await,
-            Err(e) => {
-                error!("exchange_authorization_code_for_token error: {}", e);
-                Err(e)
-            }
-        }
+        self.send_json::<TokenResponse>(self.client.post(&url).form(&params)).await
     }
 
-    async fn exchange_refresh_token_for_token(&self, refresh_token: &str) -> Result<TokenResponse, reqwest::Error> {
+    async fn exchange_refresh_token_for_token(&self, refresh_token: &str) -> Result<TokenResponse, TdaError> {
         let url = format!("{}/oauth2/token", self.base_url);
         let params = [("grant_type", "refresh_token"), ("refresh_token", refresh_token), ("client_id", &self.api_key)];
 
-        let res = self.client.post(&url).form(&params).send().await;
-        match res {
-            Ok(data) => data.json::<TokenResponse>().await,
-            Err(e) => {
-                error!("exchange_refresh_token_for_token error: {}", e);
-                Err(e)
-            }
-        }
+        self.send_json::<TokenResponse>(self.client.post(&url).form(&params)).await
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::tda_client::{auth::TDAmeritradeClientAuth, TDAmeritradeClient};
+    use crate::tda_client::{auth::TDAmeritradeClientAuth, TDAmeritradeClient, TDAmeritradeClientConfig};
 
     #[test]
     fn test_get_authorization_url() {
-        use dotenv::dotenv;
-        dotenv().ok();
-        let client = TDAmeritradeClient::new();
+        let client = TDAmeritradeClient::with_config(TDAmeritradeClientConfig {
+            api_key: "GUUU9EWYV1ULXCG7GCTSQDFI73FHZGNT".to_string(),
+            base_url: "https://api.tdameritrade.com/v1".to_string(),
+            auth_url: "https://auth.tdameritrade.com/auth".to_string(),
+            callback_url: "https://localhost:3000/api/auth/callback/tda".to_string(),
+        });
         let url = client.get_authorization_url();
         assert_eq!(
             url,
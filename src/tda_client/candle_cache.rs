@@ -0,0 +1,122 @@
+use super::{
+    error::TdaError,
+    price_history::{PriceHistory, PriceHistoryQuery, TDAmeritradeClientPriceHistory},
+    TDAmeritradeClient,
+};
+use crate::database_client::{repository::CandleRepository, CandleSeries};
+use chrono::Utc;
+use log::error;
+use std::sync::Arc;
+
+/// Serves price history from the database when the requested range was fetched before.
+/// Only bars that had already closed when they were fetched are stored, so a cached
+/// range never contains a candle that could still change.
+#[derive(Clone)]
+pub struct CandleCache {
+    tda_client: TDAmeritradeClient,
+    candles: Arc<dyn CandleRepository>,
+}
+
+impl CandleCache {
+    pub fn new(tda_client: TDAmeritradeClient, candles: Arc<dyn CandleRepository>) -> Self {
+        Self { tda_client, candles }
+    }
+
+    pub async fn price_history(&self, token: &str, symbol: &str, query: &PriceHistoryQuery) -> Result<PriceHistory, TdaError> {
+        // Period queries are relative to now, so only explicit date ranges are cacheable.
+        let (series, start, end) = match (query.frequency_type, query.frequency, query.start_date, query.end_date) {
+            (Some(frequency_type), Some(frequency), Some(start), Some(end)) => (
+                CandleSeries {
+                    symbol: symbol.to_string(),
+                    frequency_type,
+                    frequency,
+                    extended_hours: query.need_extended_hours_data.unwrap_or(true),
+                },
+                start,
+                end,
+            ),
+            _ => return self.tda_client.get_price_history(token, symbol, query).await,
+        };
+
+        match self.candles.get_cached_candles(&series, start, end).await {
+            Ok(Some(candles)) => {
+                return Ok(PriceHistory {
+                    symbol: symbol.to_string(),
+                    empty: candles.is_empty(),
+                    candles,
+                })
+            }
+            Ok(None) => {}
+            Err(e) => error!("Error reading cached candles for {}: {}", symbol, e),
+        }
+
+        let history = self.tda_client.get_price_history(token, symbol, query).await?;
+        let bar_millis = series.frequency_type.bar_millis(series.frequency);
+        let completed_before = Utc::now().timestamp_millis() - bar_millis;
+        let cached_end = end.min(completed_before);
+        if cached_end >= start {
+            let completed = history.candles.iter().filter(|candle| candle.datetime <= cached_end).cloned().collect();
+            if let Err(e) = self.candles.cache_candles(&series, start, cached_end, completed).await {
+                error!("Error caching candles for {}: {}", symbol, e);
+            }
+        }
+        Ok(history)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CandleCache;
+    use crate::{
+        database_client::memory::InMemoryDatabase,
+        tda_client::{
+            mock::{MockTda, ACCESS_TOKEN},
+            price_history::{FrequencyType, PriceHistoryQuery},
+        },
+    };
+    use chrono::Utc;
+    use std::sync::Arc;
+
+    fn query(start_date: i64, end_date: i64) -> PriceHistoryQuery {
+        PriceHistoryQuery {
+            frequency_type: Some(FrequencyType::Minute),
+            frequency: Some(5),
+            start_date: Some(start_date),
+            end_date: Some(end_date),
+            ..PriceHistoryQuery::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_historical_range_is_served_from_cache() {
+        let tda = MockTda::start();
+        let cache = CandleCache::new(tda.client(), Arc::new(InMemoryDatabase::default()));
+        let query = query(1677679200000, 1677680100000);
+
+        let fetched = cache.price_history(ACCESS_TOKEN, "AAPL", &query).await.unwrap();
+        let cached = cache.price_history(ACCESS_TOKEN, "AAPL", &query).await.unwrap();
+
+        assert_eq!(fetched.candles.len(), 3);
+        assert_eq!(cached.candles, fetched.candles);
+        assert_eq!(tda.requests(), vec!["GET /v1/marketdata/AAPL/pricehistory"]);
+    }
+
+    #[tokio::test]
+    async fn test_open_bars_and_period_queries_are_not_cached() {
+        let tda = MockTda::start();
+        let cache = CandleCache::new(tda.client(), Arc::new(InMemoryDatabase::default()));
+        let now = Utc::now().timestamp_millis();
+        let live = query(now - 60_000, now);
+        let period = PriceHistoryQuery {
+            frequency_type: Some(FrequencyType::Minute),
+            frequency: Some(5),
+            ..PriceHistoryQuery::default()
+        };
+
+        for query in [&live, &live, &period, &period] {
+            cache.price_history(ACCESS_TOKEN, "AAPL", query).await.unwrap();
+        }
+
+        assert_eq!(tda.requests().len(), 4);
+    }
+}
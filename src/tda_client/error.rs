@@ -0,0 +1,126 @@
+use crate::{database_client::error::DatabaseError, handlers::error::ErrorBody};
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+/// Error body returned by TDA, `{"error": "..."}`.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct TDAmeritradeClientError {
+    pub error: String,
+}
+
+#[derive(Debug)]
+pub enum TdaError {
+    /// The access token is missing, expired or was rejected by TDA.
+    Unauthorized(String),
+    /// TDA throttled the request; carries the `Retry-After` seconds when TDA sent one.
+    RateLimited(Option<u64>),
+    /// TDA rejected the request with a 4xx other than 401 and 429.
+    Rejected { status: StatusCode, message: String },
+    /// TDA failed with a 5xx.
+    Upstream { status: StatusCode, message: String },
+    /// TDA answered successfully but the body did not match our model.
+    Decode { message: String, body: String },
+    /// The request never produced a response.
+    Transport(reqwest::Error),
+    /// The user's TDA tokens could not be loaded from the database.
+    Storage(DatabaseError),
+}
+
+impl TdaError {
+    pub fn from_response(status: StatusCode, retry_after: Option<u64>, body: String) -> Self {
+        let message = match serde_json::from_str::<TDAmeritradeClientError>(&body) {
+            Ok(error) => error.error,
+            Err(_) => body,
+        };
+        match status {
+            StatusCode::UNAUTHORIZED => TdaError::Unauthorized(message),
+            StatusCode::TOO_MANY_REQUESTS => TdaError::RateLimited(retry_after),
+            status if status.is_server_error() => TdaError::Upstream { status, message },
+            status => TdaError::Rejected { status, message },
+        }
+    }
+
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            TdaError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            TdaError::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
+            TdaError::Rejected { status, .. } => *status,
+            TdaError::Upstream { .. } | TdaError::Decode { .. } => StatusCode::BAD_GATEWAY,
+            TdaError::Transport(e) if e.is_timeout() => StatusCode::GATEWAY_TIMEOUT,
+            TdaError::Transport(_) => StatusCode::BAD_GATEWAY,
+            TdaError::Storage(DatabaseError::PoolTimeout) => StatusCode::SERVICE_UNAVAILABLE,
+            TdaError::Storage(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl Display for TdaError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            TdaError::Unauthorized(message) => write!(f, "unauthorized: {}", message),
+            TdaError::RateLimited(Some(seconds)) => write!(f, "rate limited, retry after {}s", seconds),
+            TdaError::RateLimited(None) => write!(f, "rate limited"),
+            TdaError::Rejected { status, message } => write!(f, "rejected with {}: {}", status, message),
+            TdaError::Upstream { status, message } => write!(f, "upstream error {}: {}", status, message),
+            TdaError::Decode { message, .. } => write!(f, "decode error: {}", message),
+            TdaError::Transport(e) => write!(f, "transport error: {}", e),
+            TdaError::Storage(e) => write!(f, "storage error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for TdaError {}
+
+impl From<reqwest::Error> for TdaError {
+    fn from(e: reqwest::Error) -> Self {
+        TdaError::Transport(e)
+    }
+}
+
+impl IntoResponse for TdaError {
+    fn into_response(self) -> Response {
+        let status = self.status_code();
+        let mut response = (status, Json(ErrorBody { error: self.to_string() })).into_response();
+        if let TdaError::RateLimited(Some(seconds)) = self {
+            response.headers_mut().insert(hyper::header::RETRY_AFTER, seconds.into());
+        }
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TdaError;
+    use axum::{http::StatusCode, response::IntoResponse};
+
+    #[test]
+    fn test_from_response_status_mapping() {
+        let error = TdaError::from_response(StatusCode::UNAUTHORIZED, None, r#"{"error":"The access token being passed has expired or is invalid."}"#.to_string());
+        assert!(matches!(error, TdaError::Unauthorized(ref message) if message.starts_with("The access token")));
+        assert!(matches!(
+            TdaError::from_response(StatusCode::TOO_MANY_REQUESTS, Some(30), String::new()),
+            TdaError::RateLimited(Some(30))
+        ));
+        assert!(matches!(TdaError::from_response(StatusCode::BAD_REQUEST, None, "bad".to_string()), TdaError::Rejected { .. }));
+        assert!(matches!(TdaError::from_response(StatusCode::SERVICE_UNAVAILABLE, None, String::new()), TdaError::Upstream { .. }));
+    }
+
+    #[test]
+    fn test_into_response() {
+        let response = TdaError::RateLimited(Some(30)).into_response();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()["retry-after"], "30");
+
+        let response = TdaError::Decode {
+            message: "missing field `orderId`".to_string(),
+            body: "[]".to_string(),
+        }
+        .into_response();
+        assert_eq!(response.status(), StatusCode::BAD_GATEWAY);
+    }
+}
@@ -0,0 +1,204 @@
+use super::{error::TdaError, TDAmeritradeClient};
+use async_trait::async_trait;
+use axum::http::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Projection {
+    /// Exact symbol match
+    #[default]
+    SymbolSearch,
+    /// Symbols matching a regular expression, e.g. `AAP.*`
+    SymbolRegex,
+    /// Descriptions containing the search text
+    DescSearch,
+    /// Descriptions matching a regular expression
+    DescRegex,
+    /// Exact symbol match including fundamental data
+    Fundamental,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum InstrumentAssetType {
+    Equity,
+    Etf,
+    Forex,
+    Future,
+    FutureOption,
+    Index,
+    Indicator,
+    MutualFund,
+    Option,
+    Bond,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Fundamental {
+    pub symbol: String,
+    pub high52: f64,
+    pub low52: f64,
+    pub dividend_amount: f64,
+    pub dividend_yield: f64,
+    pub dividend_date: String,
+    pub pe_ratio: f64,
+    pub peg_ratio: f64,
+    pub pb_ratio: f64,
+    pub pr_ratio: f64,
+    pub pcf_ratio: f64,
+    #[serde(rename = "grossMarginTTM")]
+    pub gross_margin_ttm: f64,
+    #[serde(rename = "grossMarginMRQ")]
+    pub gross_margin_mrq: f64,
+    #[serde(rename = "netProfitMarginTTM")]
+    pub net_profit_margin_ttm: f64,
+    #[serde(rename = "netProfitMarginMRQ")]
+    pub net_profit_margin_mrq: f64,
+    #[serde(rename = "operatingMarginTTM")]
+    pub operating_margin_ttm: f64,
+    #[serde(rename = "operatingMarginMRQ")]
+    pub operating_margin_mrq: f64,
+    pub return_on_equity: f64,
+    pub return_on_assets: f64,
+    pub return_on_investment: f64,
+    pub quick_ratio: f64,
+    pub current_ratio: f64,
+    pub interest_coverage: f64,
+    pub total_debt_to_capital: f64,
+    pub lt_debt_to_equity: f64,
+    pub total_debt_to_equity: f64,
+    #[serde(rename = "epsTTM")]
+    pub eps_ttm: f64,
+    #[serde(rename = "epsChangePercentTTM")]
+    pub eps_change_percent_ttm: f64,
+    pub eps_change_year: f64,
+    pub eps_change: f64,
+    pub rev_change_year: f64,
+    #[serde(rename = "revChangeTTM")]
+    pub rev_change_ttm: f64,
+    pub rev_change_in: f64,
+    pub shares_outstanding: f64,
+    pub market_cap_float: f64,
+    pub market_cap: f64,
+    pub book_value_per_share: f64,
+    pub short_int_to_float: f64,
+    pub short_int_day_to_cover: f64,
+    pub div_growth_rate3_year: f64,
+    pub dividend_pay_amount: f64,
+    pub dividend_pay_date: String,
+    pub beta: f64,
+    pub vol1_day_avg: f64,
+    pub vol10_day_avg: f64,
+    pub vol3_month_avg: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstrumentInfo {
+    #[serde(default)]
+    pub cusip: Option<String>,
+    pub symbol: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub exchange: String,
+    pub asset_type: InstrumentAssetType,
+    /// Only present for [`Projection::Fundamental`] searches.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fundamental: Option<Fundamental>,
+}
+
+#[derive(Serialize)]
+struct SearchInstrumentsQuery<'a> {
+    symbol: &'a str,
+    projection: Projection,
+}
+
+#[async_trait]
+pub trait TDAmeritradeClientInstruments {
+    /// Matches sorted by symbol; TDA returns them as an object keyed by symbol.
+    async fn search_instruments(&self, token: &str, symbol: &str, projection: Projection) -> Result<Vec<InstrumentInfo>, TdaError>;
+    async fn get_instrument(&self, token: &str, cusip: &str) -> Result<InstrumentInfo, TdaError>;
+    async fn get_fundamental(&self, token: &str, symbol: &str) -> Result<Fundamental, TdaError>;
+}
+
+fn not_found(what: &str) -> TdaError {
+    TdaError::Rejected {
+        status: StatusCode::NOT_FOUND,
+        message: format!("no instrument for {}", what),
+    }
+}
+
+#[async_trait]
+impl TDAmeritradeClientInstruments for TDAmeritradeClient {
+    async fn search_instruments(&self, token: &str, symbol: &str, projection: Projection) -> Result<Vec<InstrumentInfo>, TdaError> {
+        let url = format!("{}/instruments", self.base_url);
+        let query = SearchInstrumentsQuery { symbol, projection };
+        let instruments = self.send_json::<HashMap<String, InstrumentInfo>>(self.client.get(&url).bearer_auth(token).query(&query)).await?;
+        let mut instruments: Vec<InstrumentInfo> = instruments.into_values().collect();
+        instruments.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+        Ok(instruments)
+    }
+
+    async fn get_instrument(&self, token: &str, cusip: &str) -> Result<InstrumentInfo, TdaError> {
+        let url = format!("{}/instruments/{}", self.base_url, cusip);
+        let instruments = self.send_json::<Vec<InstrumentInfo>>(self.client.get(&url).bearer_auth(token)).await?;
+        instruments.into_iter().next().ok_or_else(|| not_found(cusip))
+    }
+
+    async fn get_fundamental(&self, token: &str, symbol: &str) -> Result<Fundamental, TdaError> {
+        let instruments = self.search_instruments(token, symbol, Projection::Fundamental).await?;
+        instruments.into_iter().find_map(|instrument| instrument.fundamental).ok_or_else(|| not_found(symbol))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{InstrumentAssetType, InstrumentInfo, Projection, TDAmeritradeClientInstruments};
+    use crate::tda_client::{
+        error::TdaError,
+        mock::{MockTda, ACCESS_TOKEN},
+    };
+    use axum::http::StatusCode;
+    use std::collections::HashMap;
+
+    const INSTRUMENTS: &str = include_str!("fixtures/instruments.json");
+
+    #[test]
+    fn test_deserialize_instruments() {
+        let instruments = serde_json::from_str::<HashMap<String, InstrumentInfo>>(INSTRUMENTS).unwrap();
+        let aapl = &instruments["AAPL"];
+        assert_eq!(aapl.asset_type, InstrumentAssetType::Equity);
+        assert_eq!(aapl.cusip.as_deref(), Some("037833100"));
+        let fundamental = aapl.fundamental.as_ref().unwrap();
+        assert_eq!(fundamental.pe_ratio, 25.1783);
+        assert_eq!(fundamental.eps_ttm, 5.8911);
+        assert_eq!(instruments["SPY"].asset_type, InstrumentAssetType::Etf);
+        assert!(instruments["SPY"].fundamental.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_instruments() {
+        let tda = MockTda::start();
+        let client = tda.client();
+        let matches = client.search_instruments(ACCESS_TOKEN, "AAP.*", Projection::SymbolRegex).await.unwrap();
+        let symbols: Vec<&str> = matches.iter().map(|instrument| instrument.symbol.as_str()).collect();
+        assert_eq!(symbols, vec!["AAPL", "AAPU"]);
+        assert!(matches.iter().all(|instrument| instrument.fundamental.is_none()));
+        assert_eq!(client.get_fundamental(ACCESS_TOKEN, "AAPL").await.unwrap().symbol, "AAPL");
+        assert_eq!(client.get_instrument(ACCESS_TOKEN, "78462F103").await.unwrap().symbol, "SPY");
+        assert!(matches!(
+            client.get_fundamental(ACCESS_TOKEN, "NOPE").await,
+            Err(TdaError::Rejected { status: StatusCode::NOT_FOUND, .. })
+        ));
+        assert!(matches!(
+            client.get_instrument(ACCESS_TOKEN, "000000000").await,
+            Err(TdaError::Rejected { status: StatusCode::NOT_FOUND, .. })
+        ));
+    }
+}
@@ -0,0 +1,101 @@
+use super::{
+    accounts::Session,
+    error::TdaError,
+    market_hours::{Market, MarketHours, SessionInterval, TDAmeritradeClientMarketHours},
+    TDAmeritradeClient,
+};
+use chrono::{DateTime, NaiveDate, Utc};
+
+/// Trading sessions of one product (e.g. `EQ`) over the days it was loaded for. Questions
+/// about times outside those days are answered as if the market were closed.
+#[derive(Clone, Debug, Default)]
+pub struct MarketCalendar {
+    days: Vec<MarketHours>,
+}
+
+impl MarketCalendar {
+    pub fn new(mut days: Vec<MarketHours>) -> Self {
+        days.sort_by_key(|day| day.date);
+        Self { days }
+    }
+
+    /// Fetches `product`'s hours for every day from `from` to `to`, inclusive.
+    pub async fn load(tda_client: &TDAmeritradeClient, token: &str, market: Market, product: &str, from: NaiveDate, to: NaiveDate) -> Result<Self, TdaError> {
+        let mut days = vec![];
+        for date in from.iter_days().take_while(|date| *date <= to) {
+            let hours = tda_client.get_market_hours(token, market, date).await?;
+            days.extend(hours.into_iter().filter(|hours| hours.product == product));
+        }
+        Ok(Self::new(days))
+    }
+
+    fn intervals(&self) -> impl Iterator<Item = (Session, &SessionInterval)> {
+        self.days.iter().filter(|day| day.is_open).filter_map(|day| day.session_hours.as_ref()).flat_map(|sessions| {
+            let pre = sessions.pre_market.iter().map(|interval| (Session::Am, interval));
+            let regular = sessions.regular_market.iter().map(|interval| (Session::Normal, interval));
+            let post = sessions.post_market.iter().map(|interval| (Session::Pm, interval));
+            pre.chain(regular).chain(post)
+        })
+    }
+
+    /// The session trading at `at`, or `None` while the market is closed.
+    pub fn session(&self, at: DateTime<Utc>) -> Option<Session> {
+        self.intervals().find(|(_, interval)| interval.start <= at && at < interval.end).map(|(session, _)| session)
+    }
+
+    /// Whether the regular session is trading at `at`; extended hours don't count.
+    pub fn is_open(&self, at: DateTime<Utc>) -> bool {
+        self.session(at) == Some(Session::Normal)
+    }
+
+    /// The next regular session open strictly after `after`.
+    pub fn next_open(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.regular_market().map(|interval| interval.start.with_timezone(&Utc)).filter(|start| *start > after).min()
+    }
+
+    /// The next regular session close strictly after `after`.
+    pub fn next_close(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.regular_market().map(|interval| interval.end.with_timezone(&Utc)).filter(|end| *end > after).min()
+    }
+
+    fn regular_market(&self) -> impl Iterator<Item = &SessionInterval> {
+        self.intervals().filter(|(session, _)| *session == Session::Normal).map(|(_, interval)| interval)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MarketCalendar;
+    use crate::tda_client::{
+        accounts::Session,
+        market_hours::Market,
+        mock::{MockTda, ACCESS_TOKEN},
+    };
+    use chrono::{DateTime, NaiveDate, Utc};
+
+    fn at(timestamp: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(timestamp).unwrap().with_timezone(&Utc)
+    }
+
+    #[tokio::test]
+    async fn test_sessions_across_a_weekend() {
+        let tda = MockTda::start();
+        let (friday, monday) = (NaiveDate::from_ymd_opt(2023, 3, 3).unwrap(), NaiveDate::from_ymd_opt(2023, 3, 6).unwrap());
+        let calendar = MarketCalendar::load(&tda.client(), ACCESS_TOKEN, Market::Equity, "EQ", friday, monday).await.unwrap();
+        assert_eq!(tda.requests().len(), 4);
+
+        assert_eq!(calendar.session(at("2023-03-03T08:00:00-05:00")), Some(Session::Am));
+        assert_eq!(calendar.session(at("2023-03-03T09:30:00-05:00")), Some(Session::Normal));
+        assert_eq!(calendar.session(at("2023-03-03T16:00:00-05:00")), Some(Session::Pm));
+        assert_eq!(calendar.session(at("2023-03-03T21:00:00-05:00")), None);
+        assert_eq!(calendar.session(at("2023-03-04T12:00:00-05:00")), None);
+
+        assert!(calendar.is_open(at("2023-03-03T15:59:59-05:00")));
+        assert!(!calendar.is_open(at("2023-03-03T16:00:00-05:00")));
+        assert!(!calendar.is_open(at("2023-03-03T08:00:00-05:00")));
+
+        assert_eq!(calendar.next_close(at("2023-03-03T10:00:00-05:00")), Some(at("2023-03-03T16:00:00-05:00")));
+        assert_eq!(calendar.next_open(at("2023-03-03T10:00:00-05:00")), Some(at("2023-03-06T09:30:00-05:00")));
+        assert_eq!(calendar.next_close(at("2023-03-06T16:00:00-05:00")), None);
+    }
+}
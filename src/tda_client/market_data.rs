@@ -0,0 +1,366 @@
+use super::{error::TdaError, TDAmeritradeClient};
+use async_trait::async_trait;
+use axum::http::StatusCode;
+use serde::{
+    de::{self, DeserializeOwned},
+    Deserialize, Deserializer, Serialize,
+};
+use serde_json::Value;
+use std::collections::HashMap;
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct EquityQuote {
+    pub symbol: String,
+    pub description: String,
+    pub cusip: Option<String>,
+    pub bid_price: f64,
+    pub bid_size: i64,
+    pub ask_price: f64,
+    pub ask_size: i64,
+    pub last_price: f64,
+    pub last_size: i64,
+    pub open_price: f64,
+    pub high_price: f64,
+    pub low_price: f64,
+    pub close_price: f64,
+    pub net_change: f64,
+    pub total_volume: i64,
+    pub quote_time_in_long: i64,
+    pub trade_time_in_long: i64,
+    pub mark: f64,
+    pub exchange: String,
+    pub exchange_name: String,
+    pub marginable: bool,
+    pub shortable: bool,
+    pub volatility: f64,
+    pub digits: i32,
+    #[serde(rename = "52WkHigh")]
+    pub high_52_week: f64,
+    #[serde(rename = "52WkLow")]
+    pub low_52_week: f64,
+    pub pe_ratio: f64,
+    pub div_amount: f64,
+    pub div_yield: f64,
+    pub div_date: Option<String>,
+    pub security_status: String,
+    pub regular_market_last_price: f64,
+    pub regular_market_net_change: f64,
+    pub regular_market_trade_time_in_long: i64,
+    pub delayed: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub enum OptionContractType {
+    #[serde(rename = "C")]
+    Call,
+    #[serde(rename = "P")]
+    Put,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct OptionQuote {
+    pub symbol: String,
+    pub description: String,
+    pub bid_price: f64,
+    pub bid_size: i64,
+    pub ask_price: f64,
+    pub ask_size: i64,
+    pub last_price: f64,
+    pub last_size: i64,
+    pub open_price: f64,
+    pub high_price: f64,
+    pub low_price: f64,
+    pub close_price: f64,
+    pub net_change: f64,
+    pub total_volume: i64,
+    pub quote_time_in_long: i64,
+    pub trade_time_in_long: i64,
+    pub mark: f64,
+    pub open_interest: i64,
+    pub volatility: f64,
+    pub money_intrinsic_value: f64,
+    pub multiplier: f64,
+    pub strike_price: f64,
+    pub contract_type: Option<OptionContractType>,
+    pub underlying: String,
+    pub expiration_day: u32,
+    pub expiration_month: u32,
+    pub expiration_year: i32,
+    pub days_to_expiration: i64,
+    pub time_value: f64,
+    pub delta: f64,
+    pub gamma: f64,
+    pub theta: f64,
+    pub vega: f64,
+    pub rho: f64,
+    pub theoretical_option_value: f64,
+    pub underlying_price: f64,
+    pub exchange_name: String,
+    pub security_status: String,
+    pub delayed: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct IndexQuote {
+    pub symbol: String,
+    pub description: String,
+    pub last_price: f64,
+    pub open_price: f64,
+    pub high_price: f64,
+    pub low_price: f64,
+    pub close_price: f64,
+    pub net_change: f64,
+    pub total_volume: i64,
+    pub trade_time_in_long: i64,
+    pub exchange: String,
+    pub exchange_name: String,
+    pub digits: i32,
+    #[serde(rename = "52WkHigh")]
+    pub high_52_week: f64,
+    #[serde(rename = "52WkLow")]
+    pub low_52_week: f64,
+    pub security_status: String,
+    pub delayed: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct MutualFundQuote {
+    pub symbol: String,
+    pub description: String,
+    pub cusip: Option<String>,
+    pub close_price: f64,
+    pub net_change: f64,
+    pub total_volume: i64,
+    pub trade_time_in_long: i64,
+    pub exchange: String,
+    pub exchange_name: String,
+    pub digits: i32,
+    #[serde(rename = "52WkHigh")]
+    pub high_52_week: f64,
+    #[serde(rename = "52WkLow")]
+    pub low_52_week: f64,
+    #[serde(rename = "nAV")]
+    pub nav: f64,
+    pub pe_ratio: f64,
+    pub div_amount: f64,
+    pub div_yield: f64,
+    pub div_date: Option<String>,
+    pub security_status: String,
+    pub delayed: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ForexQuote {
+    pub symbol: String,
+    pub bid_price_in_double: f64,
+    pub ask_price_in_double: f64,
+    pub last_price_in_double: f64,
+    pub bid_size_in_long: i64,
+    pub ask_size_in_long: i64,
+    pub close_price_in_double: f64,
+    pub open_price_in_double: f64,
+    pub high_price_in_double: f64,
+    pub low_price_in_double: f64,
+    pub net_change_in_double: f64,
+    pub quote_time_in_long: i64,
+    pub trade_time_in_long: i64,
+    pub mark: f64,
+    pub exchange_name: String,
+    pub digits: i32,
+    pub security_status: String,
+    pub tick: f64,
+    pub tick_amount: f64,
+    pub product: String,
+    pub trading_hours: String,
+    pub is_tradable: bool,
+    pub market_maker: String,
+    #[serde(rename = "52WkHighInDouble")]
+    pub high_52_week_in_double: f64,
+    #[serde(rename = "52WkLowInDouble")]
+    pub low_52_week_in_double: f64,
+    pub delayed: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct FutureQuote {
+    pub symbol: String,
+    pub bid_price_in_double: f64,
+    pub ask_price_in_double: f64,
+    pub last_price_in_double: f64,
+    pub bid_size_in_long: i64,
+    pub ask_size_in_long: i64,
+    pub close_price_in_double: f64,
+    pub open_price_in_double: f64,
+    pub high_price_in_double: f64,
+    pub low_price_in_double: f64,
+    pub net_change_in_double: f64,
+    pub total_volume: i64,
+    pub quote_time_in_long: i64,
+    pub trade_time_in_long: i64,
+    pub mark: f64,
+    pub exchange_name: String,
+    pub security_status: String,
+    pub open_interest: i64,
+    pub future_percent_change: f64,
+    pub tick: f64,
+    pub tick_amount: f64,
+    pub product: String,
+    pub future_price_format: String,
+    pub future_trading_hours: String,
+    pub future_is_tradable: bool,
+    pub future_multiplier: f64,
+    pub future_is_active: bool,
+    pub future_settlement_price: f64,
+    pub future_active_symbol: String,
+    pub future_expiration_date: i64,
+    pub delayed: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "assetType", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Quote {
+    Equity(EquityQuote),
+    Etf(EquityQuote),
+    Option(OptionQuote),
+    Index(IndexQuote),
+    MutualFund(MutualFundQuote),
+    Forex(ForexQuote),
+    Future(FutureQuote),
+    /// Asset types we don't model yet, e.g. `FUTURE_OPTION`, kept as TDA sent them.
+    #[serde(untagged)]
+    Unknown(Value),
+}
+
+impl<'de> Deserialize<'de> for Quote {
+    /// Dispatches on `assetType`. Only unrecognised asset types fall back to [`Quote::Unknown`];
+    /// a known type that doesn't match its model is an error rather than a quote without a price.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        fn known<T: DeserializeOwned, E: de::Error>(value: Value) -> Result<T, E> {
+            // Every field but the symbol has a default, and TDA keys quotes by it.
+            if value.get("symbol").and_then(Value::as_str).is_none() {
+                return Err(E::missing_field("symbol"));
+            }
+            serde_json::from_value(value).map_err(E::custom)
+        }
+
+        let value = Value::deserialize(deserializer)?;
+        match value.get("assetType").and_then(Value::as_str) {
+            Some("EQUITY") => known(value).map(Quote::Equity),
+            Some("ETF") => known(value).map(Quote::Etf),
+            Some("OPTION") => known(value).map(Quote::Option),
+            Some("INDEX") => known(value).map(Quote::Index),
+            Some("MUTUAL_FUND") => known(value).map(Quote::MutualFund),
+            Some("FOREX") => known(value).map(Quote::Forex),
+            Some("FUTURE") => known(value).map(Quote::Future),
+            _ => Ok(Quote::Unknown(value)),
+        }
+    }
+}
+
+impl Quote {
+    pub fn symbol(&self) -> Option<&str> {
+        match self {
+            Quote::Equity(quote) | Quote::Etf(quote) => Some(&quote.symbol),
+            Quote::Option(quote) => Some(&quote.symbol),
+            Quote::Index(quote) => Some(&quote.symbol),
+            Quote::MutualFund(quote) => Some(&quote.symbol),
+            Quote::Forex(quote) => Some(&quote.symbol),
+            Quote::Future(quote) => Some(&quote.symbol),
+            Quote::Unknown(_) => None,
+        }
+    }
+
+    /// The price positions are marked at: the quote's mark, or the last/NAV price for
+    /// indices and mutual funds, which have no bid/ask.
+    pub fn mark(&self) -> Option<f64> {
+        match self {
+            Quote::Equity(quote) | Quote::Etf(quote) => Some(quote.mark),
+            Quote::Option(quote) => Some(quote.mark),
+            Quote::Index(quote) => Some(quote.last_price),
+            Quote::MutualFund(quote) => Some(quote.nav),
+            Quote::Forex(quote) => Some(quote.mark),
+            Quote::Future(quote) => Some(quote.mark),
+            Quote::Unknown(_) => None,
+        }
+    }
+}
+
+#[async_trait]
+pub trait TDAmeritradeClientMarketData {
+    async fn get_quote(&self, token: &str, symbol: &str) -> Result<Quote, TdaError>;
+    async fn get_quotes(&self, token: &str, symbols: &[String]) -> Result<HashMap<String, Quote>, TdaError>;
+}
+
+#[async_trait]
+impl TDAmeritradeClientMarketData for TDAmeritradeClient {
+    async fn get_quote(&self, token: &str, symbol: &str) -> Result<Quote, TdaError> {
+        let mut quotes = self.get_quotes(token, &[symbol.to_string()]).await?;
+        // TDA answers an unknown symbol with an empty object rather than a 404.
+        quotes.remove(symbol).ok_or_else(|| TdaError::Rejected {
+            status: StatusCode::NOT_FOUND,
+            message: format!("no quote for {}", symbol),
+        })
+    }
+
+    async fn get_quotes(&self, token: &str, symbols: &[String]) -> Result<HashMap<String, Quote>, TdaError> {
+        let url = format!("{}/marketdata/quotes", self.base_url);
+        let query = [("symbol", symbols.join(","))];
+        self.send_json::<HashMap<String, Quote>>(self.client.get(&url).bearer_auth(token).query(&query)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OptionContractType, Quote, TDAmeritradeClientMarketData};
+    use crate::tda_client::{
+        error::TdaError,
+        mock::{MockTda, ACCESS_TOKEN},
+    };
+    use axum::http::StatusCode;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    const QUOTES: &str = include_str!("fixtures/quotes.json");
+
+    #[test]
+    fn test_deserialize_quotes() {
+        let quotes = serde_json::from_str::<HashMap<String, Quote>>(QUOTES).unwrap();
+        assert!(matches!(&quotes["AAPL"], Quote::Equity(quote) if quote.high_52_week == 179.61));
+        assert!(matches!(&quotes["SPY"], Quote::Etf(_)));
+        match &quotes["AAPL_031723C150"] {
+            Quote::Option(quote) => {
+                assert_eq!(quote.contract_type, Some(OptionContractType::Call));
+                assert_eq!(quote.delta, 0.28);
+            }
+            quote => panic!("unexpected quote: {:?}", quote),
+        }
+        assert_eq!(quotes["$SPX.X"].mark(), Some(3981.35));
+        assert_eq!(quotes["VFIAX"].mark(), Some(364.48));
+        assert_eq!(quotes["EUR/USD"].symbol(), Some("EUR/USD"));
+        assert!(matches!(&quotes["/ES"], Quote::Future(quote) if quote.future_multiplier == 50.0));
+        let unknown = serde_json::from_str::<Quote>(r#"{"assetType":"FUTURE_OPTION","symbol":"./ESH23C4000"}"#).unwrap();
+        assert_eq!(unknown, Quote::Unknown(json!({"assetType": "FUTURE_OPTION", "symbol": "./ESH23C4000"})));
+        assert_eq!(serde_json::to_value(&unknown).unwrap()["assetType"], "FUTURE_OPTION");
+        assert!(serde_json::from_str::<Quote>(r#"{"assetType":"EQUITY","description":"Apple Inc. - Common Stock","mark":142.5}"#).is_err());
+        assert!(serde_json::from_str::<Quote>(r#"{"assetType":"EQUITY","symbol":"AAPL","mark":"n/a"}"#).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_quotes() {
+        let tda = MockTda::start();
+        let client = tda.client();
+        let quotes = client.get_quotes(ACCESS_TOKEN, &["AAPL".to_string(), "/ES".to_string()]).await.unwrap();
+        assert_eq!(quotes.len(), 2);
+        assert!(matches!(client.get_quote(ACCESS_TOKEN, "AAPL").await.unwrap(), Quote::Equity(_)));
+        assert!(matches!(client.get_quote(ACCESS_TOKEN, "NOPE").await, Err(TdaError::Rejected { status: StatusCode::NOT_FOUND, .. })));
+
+        tda.respond_next(StatusCode::OK, r#"{"AAPL":{"assetType":"EQUITY","description":"Apple Inc. - Common Stock","mark":142.5}}"#);
+        assert!(matches!(client.get_quotes(ACCESS_TOKEN, &["AAPL".to_string()]).await, Err(TdaError::Decode { .. })));
+    }
+}
@@ -0,0 +1,106 @@
+use super::{error::TdaError, TDAmeritradeClient};
+use async_trait::async_trait;
+use chrono::{DateTime, FixedOffset, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Market {
+    Equity,
+    Option,
+    Future,
+    Bond,
+    Forex,
+}
+
+impl Market {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Market::Equity => "EQUITY",
+            Market::Option => "OPTION",
+            Market::Future => "FUTURE",
+            Market::Bond => "BOND",
+            Market::Forex => "FOREX",
+        }
+    }
+}
+
+/// Start and end carry the exchange's UTC offset, e.g. `2023-03-01T09:30:00-05:00`.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct SessionInterval {
+    pub start: DateTime<FixedOffset>,
+    pub end: DateTime<FixedOffset>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SessionHours {
+    pub pre_market: Vec<SessionInterval>,
+    pub regular_market: Vec<SessionInterval>,
+    pub post_market: Vec<SessionInterval>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketHours {
+    pub date: NaiveDate,
+    pub market_type: Market,
+    /// Product code, e.g. `EQ` for equities or `EQO` and `IND` for equity and index options.
+    pub product: String,
+    #[serde(default)]
+    pub product_name: Option<String>,
+    pub is_open: bool,
+    /// Absent on days the market is closed.
+    #[serde(default)]
+    pub session_hours: Option<SessionHours>,
+}
+
+#[derive(Serialize)]
+struct MarketHoursQuery {
+    date: NaiveDate,
+}
+
+#[async_trait]
+pub trait TDAmeritradeClientMarketHours {
+    /// Hours of every product traded in `market` on `date`.
+    async fn get_market_hours(&self, token: &str, market: Market, date: NaiveDate) -> Result<Vec<MarketHours>, TdaError>;
+}
+
+#[async_trait]
+impl TDAmeritradeClientMarketHours for TDAmeritradeClient {
+    async fn get_market_hours(&self, token: &str, market: Market, date: NaiveDate) -> Result<Vec<MarketHours>, TdaError> {
+        let url = format!("{}/marketdata/{}/hours", self.base_url, market.as_str());
+        // Keyed by lowercase market and then by product.
+        let markets = self
+            .send_json::<HashMap<String, HashMap<String, MarketHours>>>(self.client.get(&url).bearer_auth(token).query(&MarketHoursQuery { date }))
+            .await?;
+        let mut hours: Vec<MarketHours> = markets.into_values().flat_map(HashMap::into_values).collect();
+        hours.sort_by(|a, b| a.product.cmp(&b.product));
+        Ok(hours)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Market, MarketHours};
+    use chrono::NaiveDate;
+    use std::collections::HashMap;
+
+    const MARKET_HOURS: &str = include_str!("fixtures/market_hours.json");
+
+    #[test]
+    fn test_deserialize_market_hours() {
+        let markets = serde_json::from_str::<HashMap<String, HashMap<String, MarketHours>>>(MARKET_HOURS).unwrap();
+        let equity = &markets["equity"]["EQ"];
+        assert_eq!(equity.market_type, Market::Equity);
+        assert_eq!(equity.date, NaiveDate::from_ymd_opt(2023, 3, 1).unwrap());
+        let sessions = equity.session_hours.as_ref().unwrap();
+        assert_eq!(sessions.regular_market[0].start.to_rfc3339(), "2023-03-01T09:30:00-05:00");
+        assert_eq!(sessions.post_market[0].end.to_rfc3339(), "2023-03-01T20:00:00-05:00");
+
+        let closed = serde_json::from_str::<MarketHours>(r#"{"date":"2023-03-04","marketType":"EQUITY","product":"equity","isOpen":false}"#).unwrap();
+        assert!(!closed.is_open);
+        assert_eq!(closed.session_hours, None);
+    }
+}
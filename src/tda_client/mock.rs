@@ -0,0 +1,706 @@
+//! In-process stand-in for the TDA API, serving the JSON in `fixtures/`, so the client,
+//! token refresh and handlers can be tested end to end without network access. `/ws`
+//! stands in for the streamer, which user principals point at instead of TDA's host.
+
+use super::{auth::TokenResponse, TDAmeritradeClient, TDAmeritradeClientConfig};
+use crate::{middleware::jwt::create_access_token, AppState};
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Path, Query, State,
+    },
+    http::{header, HeaderValue, Request, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Form, Json, Router,
+};
+use axum_test::TestServer;
+use chrono::{Datelike, NaiveDate};
+use cookie::Cookie;
+use serde_json::{json, Value};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    net::{SocketAddr, TcpListener},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::sync::{broadcast, oneshot};
+use uuid::Uuid;
+
+pub const ACCESS_TOKEN: &str = "mock-access-token";
+pub const REFRESH_TOKEN: &str = "mock-refresh-token";
+pub const AUTHORIZATION_CODE: &str = "mock-authorization-code";
+pub const ACCOUNT_ID: &str = "123456789";
+pub const PLACED_ORDER_ID: i64 = 1003;
+pub const CREATED_WATCHLIST_ID: &str = "1750891232";
+pub const CREATED_SAVED_ORDER_ID: i64 = 5003;
+pub const STREAMER_TOKEN: &str = "mock-streamer-token";
+
+const STREAMER_HEARTBEAT_INTERVAL: Duration = Duration::from_millis(100);
+
+const ACCOUNTS: &str = include_str!("fixtures/accounts.json");
+const CHAINS: &str = include_str!("fixtures/chains.json");
+const INSTRUMENTS: &str = include_str!("fixtures/instruments.json");
+const MARKET_HOURS: &str = include_str!("fixtures/market_hours.json");
+const MOVERS: &str = include_str!("fixtures/movers.json");
+const ORDERS: &str = include_str!("fixtures/orders.json");
+const PRICE_HISTORY: &str = include_str!("fixtures/pricehistory.json");
+const QUOTES: &str = include_str!("fixtures/quotes.json");
+const SAVED_ORDERS: &str = include_str!("fixtures/saved_orders.json");
+const TOKEN: &str = include_str!("fixtures/token.json");
+const TRANSACTIONS: &str = include_str!("fixtures/transactions.json");
+const USER_PRINCIPALS: &str = include_str!("fixtures/user_principals.json");
+const WATCHLISTS: &str = include_str!("fixtures/watchlists.json");
+
+struct CannedResponse {
+    status: StatusCode,
+    retry_after: Option<u64>,
+    body: String,
+}
+
+struct MockState {
+    access_tokens: HashSet<String>,
+    issued_tokens: usize,
+    canned_responses: VecDeque<CannedResponse>,
+    requests: Vec<String>,
+    queries: Vec<String>,
+    streamer_url: String,
+    streamer_requests: Vec<String>,
+    streamer_muted: bool,
+    streamer_events: broadcast::Sender<StreamerEvent>,
+}
+
+#[derive(Clone, Debug)]
+enum StreamerEvent {
+    Send(Value),
+    Drop,
+}
+
+type SharedState = Arc<Mutex<MockState>>;
+
+pub struct MockTda {
+    addr: SocketAddr,
+    state: SharedState,
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+impl MockTda {
+    /// Binds to an ephemeral localhost port. [`ACCESS_TOKEN`] is accepted until [`MockTda::expire_access_tokens`].
+    pub fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Error binding mock TDA server");
+        let addr = listener.local_addr().unwrap();
+        let state = Arc::new(Mutex::new(MockState {
+            access_tokens: HashSet::from([ACCESS_TOKEN.to_string()]),
+            issued_tokens: 0,
+            canned_responses: VecDeque::new(),
+            requests: vec![],
+            queries: vec![],
+            streamer_url: format!("ws://{}/ws", addr),
+            streamer_requests: vec![],
+            streamer_muted: false,
+            streamer_events: broadcast::channel(16).0,
+        }));
+        let api = Router::new()
+            .route("/oauth2/token", post(token))
+            .route("/accounts", get(accounts))
+            .route("/accounts/:account_id", get(account))
+            .route("/accounts/:account_id/orders", get(orders).post(place_order))
+            .route("/accounts/:account_id/orders/:order_id", get(order).put(replace_order).delete(cancel_order))
+            .route("/orders", get(orders_for_all_accounts))
+            .route("/accounts/:account_id/savedorders", get(saved_orders).post(create_saved_order))
+            .route(
+                "/accounts/:account_id/savedorders/:saved_order_id",
+                get(saved_order).put(update_saved_order).delete(update_saved_order),
+            )
+            .route("/accounts/:account_id/transactions", get(transactions))
+            .route("/accounts/:account_id/transactions/:transaction_id", get(transaction))
+            .route("/accounts/watchlists", get(all_watchlists))
+            .route("/accounts/:account_id/watchlists", get(watchlists).post(create_watchlist))
+            .route(
+                "/accounts/:account_id/watchlists/:watchlist_id",
+                get(watchlist).put(update_watchlist).patch(update_watchlist).delete(update_watchlist),
+            )
+            .route("/userprincipals", get(user_principals))
+            .route("/marketdata/quotes", get(quotes))
+            .route("/marketdata/chains", get(chains))
+            // The router needs one parameter name per segment; here it holds the market.
+            .route("/marketdata/:symbol/hours", get(market_hours))
+            .route("/marketdata/:symbol/movers", get(movers))
+            .route("/instruments", get(instruments))
+            .route("/instruments/:cusip", get(instrument))
+            .route("/marketdata/:symbol/pricehistory", get(price_history));
+        let router = Router::new()
+            .nest("/v1", api)
+            .layer(middleware::from_fn_with_state(state.clone(), intercept))
+            // The streamer authenticates with its LOGIN request rather than a bearer token.
+            .route("/ws", get(streamer))
+            .with_state(state.clone());
+
+        let (shutdown, shutdown_rx) = oneshot::channel::<()>();
+        let server = axum::Server::from_tcp(listener).unwrap().serve(router.into_make_service()).with_graceful_shutdown(async {
+            shutdown_rx.await.ok();
+        });
+        tokio::spawn(server);
+        Self {
+            addr,
+            state,
+            shutdown: Some(shutdown),
+        }
+    }
+
+    pub fn base_url(&self) -> String {
+        format!("http://{}/v1", self.addr)
+    }
+
+    pub fn config(&self) -> TDAmeritradeClientConfig {
+        TDAmeritradeClientConfig {
+            api_key: "MOCKAPIKEY".to_string(),
+            base_url: self.base_url(),
+            auth_url: format!("http://{}/auth", self.addr),
+            callback_url: "http://localhost:3000/api/auth/callback/tda".to_string(),
+        }
+    }
+
+    pub fn client(&self) -> TDAmeritradeClient {
+        TDAmeritradeClient::with_config(self.config())
+    }
+
+    /// Rejects every access token issued so far with a 401, as TDA does once they expire.
+    pub fn expire_access_tokens(&self) {
+        self.state.lock().unwrap().access_tokens.clear();
+    }
+
+    /// Answers the next request, whatever its route, with `status` and `body`.
+    pub fn respond_next(&self, status: StatusCode, body: &str) {
+        self.state.lock().unwrap().canned_responses.push_back(CannedResponse {
+            status,
+            retry_after: None,
+            body: body.to_string(),
+        });
+    }
+
+    pub fn rate_limit_next(&self, retry_after: u64) {
+        self.state.lock().unwrap().canned_responses.push_back(CannedResponse {
+            status: StatusCode::TOO_MANY_REQUESTS,
+            retry_after: Some(retry_after),
+            body: String::new(),
+        });
+    }
+
+    /// Requests received so far, as `"METHOD /v1/path"`.
+    pub fn requests(&self) -> Vec<String> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    /// Query strings of the requests received so far, in the order of [`MockTda::requests`].
+    pub fn queries(&self) -> Vec<String> {
+        self.state.lock().unwrap().queries.clone()
+    }
+
+    /// Streamer requests received so far, as `"SERVICE COMMAND"`.
+    pub fn streamer_requests(&self) -> Vec<String> {
+        self.state.lock().unwrap().streamer_requests.clone()
+    }
+
+    /// Closes every open streamer connection, as if TDA had dropped them.
+    pub fn drop_streams(&self) {
+        self.state.lock().unwrap().streamer_events.send(StreamerEvent::Drop).ok();
+    }
+
+    /// Stops sending heartbeats on every streamer connection.
+    pub fn mute_streamer(&self) {
+        self.state.lock().unwrap().streamer_muted = true;
+    }
+
+    /// Sends an `ACCT_ACTIVITY` update for [`ACCOUNT_ID`] on every streamer connection.
+    pub fn push_account_activity(&self, message_type: &str, message_data: &str) {
+        let content = json!({"key": "mock-subscription-key", "1": ACCOUNT_ID, "2": message_type, "3": message_data});
+        let data = json!({"data": [{"service": "ACCT_ACTIVITY", "command": "SUBS", "content": [content]}]});
+        self.state.lock().unwrap().streamer_events.send(StreamerEvent::Send(data)).ok();
+    }
+}
+
+impl Drop for MockTda {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            shutdown.send(()).ok();
+        }
+    }
+}
+
+/// The tokens the mock issues, as TDA returns them from a code or refresh token grant.
+pub fn token_response() -> TokenResponse {
+    TokenResponse {
+        access_token: Some(ACCESS_TOKEN.to_string()),
+        refresh_token: Some(REFRESH_TOKEN.to_string()),
+        ..TokenResponse::default()
+    }
+}
+
+/// In-memory app state talking to `tda`, with one user who has already connected TDA.
+/// Returns the state and that user's access token for our API.
+pub async fn authed_state(tda: &MockTda) -> (AppState, String) {
+    let state = AppState::in_memory(tda.client());
+    let user_id = Uuid::new_v4();
+    state.token_manager.store(user_id, &token_response()).await.unwrap();
+    let access_token = create_access_token(user_id, &state.env.jwt_access_token_secret);
+    (state, access_token)
+}
+
+/// A test server for [`authed_state`] and the cookie that signs its user in.
+pub async fn authed_server(tda: &MockTda) -> (TestServer, Cookie<'static>) {
+    let (state, access_token) = authed_state(tda).await;
+    let server = TestServer::new(crate::router::Router::new(state).get_router().into_make_service()).unwrap();
+    (server, Cookie::new("access_token", access_token))
+}
+
+fn error(status: StatusCode, message: &str) -> Response {
+    (status, Json(json!({ "error": message }))).into_response()
+}
+
+async fn intercept<B>(State(state): State<SharedState>, request: Request<B>, next: Next<B>) -> Response {
+    let canned = {
+        let mut state = state.lock().unwrap();
+        state.requests.push(format!("{} {}", request.method(), request.uri().path()));
+        state.queries.push(request.uri().query().unwrap_or_default().to_string());
+        state.canned_responses.pop_front()
+    };
+    if let Some(canned) = canned {
+        let mut response = (canned.status, canned.body).into_response();
+        response.headers_mut().insert(header::CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        if let Some(retry_after) = canned.retry_after {
+            response.headers_mut().insert(header::RETRY_AFTER, retry_after.into());
+        }
+        return response;
+    }
+    if request.uri().path().ends_with("/oauth2/token") {
+        return next.run(request).await;
+    }
+    let bearer = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::to_string);
+    let authorized = bearer.is_some_and(|token| state.lock().unwrap().access_tokens.contains(&token));
+    if !authorized {
+        return error(StatusCode::UNAUTHORIZED, "The access token being passed has expired or is invalid.");
+    }
+    next.run(request).await
+}
+
+async fn token(State(state): State<SharedState>, Form(form): Form<HashMap<String, String>>) -> Response {
+    let grant_type = form.get("grant_type").map(String::as_str);
+    let valid = match grant_type {
+        Some("authorization_code") => form.get("code").map(String::as_str) == Some(AUTHORIZATION_CODE),
+        Some("refresh_token") => form.get("refresh_token").map(String::as_str) == Some(REFRESH_TOKEN),
+        _ => false,
+    };
+    if !valid {
+        return error(StatusCode::BAD_REQUEST, "invalid_grant");
+    }
+    let mut state = state.lock().unwrap();
+    state.issued_tokens += 1;
+    let access_token = format!("{}-{}", ACCESS_TOKEN, state.issued_tokens);
+    state.access_tokens.insert(access_token.clone());
+
+    let mut response = serde_json::from_str::<Value>(TOKEN).unwrap();
+    response["access_token"] = json!(access_token);
+    if grant_type == Some("refresh_token") {
+        // TDA only returns a new refresh token when asked for one with access_type=offline.
+        let response = response.as_object_mut().unwrap();
+        response.remove("refresh_token");
+        response.remove("refresh_token_expires_in");
+    }
+    Json(response).into_response()
+}
+
+async fn accounts() -> Response {
+    Json(serde_json::from_str::<Value>(ACCOUNTS).unwrap()).into_response()
+}
+
+async fn account(Path(account_id): Path<String>) -> Response {
+    let accounts = serde_json::from_str::<Vec<Value>>(ACCOUNTS).unwrap();
+    match accounts.into_iter().find(|account| account["securitiesAccount"]["accountId"] == account_id.as_str()) {
+        Some(account) => Json(account).into_response(),
+        None => error(StatusCode::NOT_FOUND, "Account not found"),
+    }
+}
+
+/// Applies the `status`, `fromEnteredTime`/`toEnteredTime` and `maxResults` filters.
+fn filter_orders(account_id: Option<&str>, query: &HashMap<String, String>) -> Vec<Value> {
+    let mut orders = serde_json::from_str::<Vec<Value>>(ORDERS).unwrap();
+    let entered_date = |order: &Value| order["enteredTime"].as_str().unwrap_or_default().get(..10).unwrap_or_default().to_string();
+    orders.retain(|order| {
+        account_id.is_none_or(|account_id| account_id.parse::<i64>().is_ok_and(|account_id| order["accountId"] == account_id))
+            && query.get("status").is_none_or(|status| order["status"] == status.as_str())
+            && query.get("fromEnteredTime").is_none_or(|from| entered_date(order) >= *from)
+            && query.get("toEnteredTime").is_none_or(|to| entered_date(order) <= *to)
+    });
+    if let Some(max_results) = query.get("maxResults").and_then(|max_results| max_results.parse::<usize>().ok()) {
+        orders.truncate(max_results);
+    }
+    orders
+}
+
+async fn orders(Path(account_id): Path<String>, Query(query): Query<HashMap<String, String>>) -> Response {
+    Json(filter_orders(Some(&account_id), &query)).into_response()
+}
+
+async fn orders_for_all_accounts(Query(query): Query<HashMap<String, String>>) -> Response {
+    Json(filter_orders(None, &query)).into_response()
+}
+
+async fn order(Path((account_id, order_id)): Path<(String, i64)>) -> Response {
+    match filter_orders(Some(&account_id), &HashMap::new()).into_iter().find(|order| order["orderId"] == order_id) {
+        Some(order) => Json(order).into_response(),
+        None => error(StatusCode::NOT_FOUND, "Order not found"),
+    }
+}
+
+fn created(account_id: &str, order_id: i64) -> Response {
+    let location = format!("/v1/accounts/{}/orders/{}", account_id, order_id);
+    (StatusCode::CREATED, [(header::LOCATION, location)]).into_response()
+}
+
+async fn place_order(Path(account_id): Path<String>, Json(_): Json<Value>) -> Response {
+    created(&account_id, PLACED_ORDER_ID)
+}
+
+async fn replace_order(Path((account_id, _)): Path<(String, i64)>, Json(_): Json<Value>) -> Response {
+    created(&account_id, PLACED_ORDER_ID + 1)
+}
+
+async fn cancel_order(Path(_): Path<(String, i64)>) -> StatusCode {
+    StatusCode::OK
+}
+
+fn account_saved_orders(account_id: &str) -> Vec<Value> {
+    let saved_orders = serde_json::from_str::<Vec<Value>>(SAVED_ORDERS).unwrap();
+    let account_id = account_id.parse::<i64>().unwrap_or_default();
+    saved_orders.into_iter().filter(|saved_order| saved_order["accountId"] == account_id).collect()
+}
+
+async fn saved_orders(Path(account_id): Path<String>) -> Response {
+    Json(account_saved_orders(&account_id)).into_response()
+}
+
+async fn saved_order(Path((account_id, saved_order_id)): Path<(String, i64)>) -> Response {
+    match account_saved_orders(&account_id).into_iter().find(|saved_order| saved_order["savedOrderId"] == saved_order_id) {
+        Some(saved_order) => Json(saved_order).into_response(),
+        None => error(StatusCode::NOT_FOUND, "Saved order not found"),
+    }
+}
+
+async fn create_saved_order(Path(account_id): Path<String>, Json(_): Json<Value>) -> Response {
+    let location = format!("/v1/accounts/{}/savedorders/{}", account_id, CREATED_SAVED_ORDER_ID);
+    (StatusCode::OK, [(header::LOCATION, location)]).into_response()
+}
+
+/// Replacing and deleting a saved order both answer 200 without a body.
+async fn update_saved_order(Path(_): Path<(String, i64)>) -> StatusCode {
+    StatusCode::OK
+}
+
+async fn transactions() -> Response {
+    Json(serde_json::from_str::<Value>(TRANSACTIONS).unwrap()).into_response()
+}
+
+async fn transaction(Path((_, transaction_id)): Path<(String, i64)>) -> Response {
+    let transactions = serde_json::from_str::<Vec<Value>>(TRANSACTIONS).unwrap();
+    match transactions.into_iter().find(|transaction| transaction["transactionId"] == transaction_id) {
+        Some(transaction) => Json(transaction).into_response(),
+        None => error(StatusCode::NOT_FOUND, "Transaction not found"),
+    }
+}
+
+async fn all_watchlists() -> Response {
+    Json(serde_json::from_str::<Value>(WATCHLISTS).unwrap()).into_response()
+}
+
+async fn watchlists(Path(account_id): Path<String>) -> Response {
+    let watchlists = serde_json::from_str::<Vec<Value>>(WATCHLISTS).unwrap();
+    Json(watchlists.into_iter().filter(|watchlist| watchlist["accountId"] == account_id.as_str()).collect::<Vec<Value>>()).into_response()
+}
+
+async fn watchlist(Path((account_id, watchlist_id)): Path<(String, String)>) -> Response {
+    let watchlists = serde_json::from_str::<Vec<Value>>(WATCHLISTS).unwrap();
+    match watchlists
+        .into_iter()
+        .find(|watchlist| watchlist["accountId"] == account_id.as_str() && watchlist["watchlistId"] == watchlist_id.as_str())
+    {
+        Some(watchlist) => Json(watchlist).into_response(),
+        None => error(StatusCode::NOT_FOUND, "Watchlist not found"),
+    }
+}
+
+async fn create_watchlist(Path(account_id): Path<String>, Json(_): Json<Value>) -> Response {
+    let location = format!("/v1/accounts/{}/watchlists/{}", account_id, CREATED_WATCHLIST_ID);
+    (StatusCode::CREATED, [(header::LOCATION, location)]).into_response()
+}
+
+/// Replacing, updating and deleting a watchlist all answer 204 without a body.
+async fn update_watchlist(Path(_): Path<(String, String)>) -> StatusCode {
+    StatusCode::NO_CONTENT
+}
+
+/// Leaves out the optional parts that weren't asked for through `fields`, like TDA does.
+async fn user_principals(State(state): State<SharedState>, Query(query): Query<HashMap<String, String>>) -> Response {
+    let mut principals = serde_json::from_str::<Value>(USER_PRINCIPALS).unwrap();
+    principals["streamerInfo"]["streamerSocketUrl"] = json!(state.lock().unwrap().streamer_url);
+    let fields = query.get("fields").map(String::as_str).unwrap_or_default();
+    let requested = |field: &str| fields.split(',').any(|requested| requested == field);
+    let principals_object = principals.as_object_mut().unwrap();
+    if !requested("streamerConnectionInfo") {
+        principals_object.remove("streamerInfo");
+    }
+    if !requested("streamerSubscriptionKeys") {
+        principals_object.remove("streamerSubscriptionKeys");
+    }
+    for account in principals_object["accounts"].as_array_mut().unwrap() {
+        let account = account.as_object_mut().unwrap();
+        if !requested("preferences") {
+            account.remove("preferences");
+        }
+        if !requested("surrogateIds") {
+            account.remove("surrogateIds");
+        }
+    }
+    Json(principals).into_response()
+}
+
+async fn quotes(Query(query): Query<HashMap<String, String>>) -> Response {
+    let mut quotes = serde_json::from_str::<HashMap<String, Value>>(QUOTES).unwrap();
+    let symbols = query.get("symbol").map(String::as_str).unwrap_or_default();
+    quotes.retain(|symbol, _| symbols.split(',').any(|requested| requested == symbol));
+    Json(quotes).into_response()
+}
+
+async fn chains(Query(query): Query<HashMap<String, String>>) -> Response {
+    match query.get("symbol").map(String::as_str) {
+        Some("AAPL") => Json(serde_json::from_str::<Value>(CHAINS).unwrap()).into_response(),
+        // TDA answers an unknown underlying with a failed, empty chain rather than an error.
+        _ => Json(json!({"symbol": query.get("symbol"), "status": "FAILED", "callExpDateMap": {}, "putExpDateMap": {}})).into_response(),
+    }
+}
+
+/// Equities trade 07:00-20:00 Eastern (standard time) on weekdays; everything else is closed.
+async fn market_hours(Path(market): Path<String>, Query(query): Query<HashMap<String, String>>) -> Response {
+    let date = query.get("date").and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok());
+    let date = match date {
+        Some(date) => date,
+        None => return error(StatusCode::BAD_REQUEST, "date is required"),
+    };
+    if market == "EQUITY" && date.weekday().num_days_from_monday() < 5 {
+        let hours = MARKET_HOURS.replace("2023-03-01", &date.to_string());
+        return Json(serde_json::from_str::<Value>(&hours).unwrap()).into_response();
+    }
+    let product = market.to_lowercase();
+    Json(json!({ &product: { &product: {"date": date, "marketType": market, "product": product, "isOpen": false} } })).into_response()
+}
+
+/// The same movers for every index; `change=percent` reports changes as fractions like TDA.
+async fn movers(Query(query): Query<HashMap<String, String>>) -> Response {
+    let mut movers = serde_json::from_str::<Vec<Value>>(MOVERS).unwrap();
+    if let Some(direction) = query.get("direction") {
+        movers.retain(|mover| mover["direction"] == direction.as_str());
+    }
+    if query.get("change").map(String::as_str) == Some("percent") {
+        for mover in movers.iter_mut() {
+            let (change, last) = (mover["change"].as_f64().unwrap(), mover["last"].as_f64().unwrap());
+            mover["change"] = json!(change / (last - change));
+        }
+    }
+    Json(movers).into_response()
+}
+
+/// Supports the projections the backend uses; `symbol-regex` only understands a trailing `.*`.
+async fn instruments(Query(query): Query<HashMap<String, String>>) -> Response {
+    let mut instruments = serde_json::from_str::<HashMap<String, Value>>(INSTRUMENTS).unwrap();
+    let symbol = query.get("symbol").cloned().unwrap_or_default();
+    let projection = query.get("projection").map(String::as_str).unwrap_or("symbol-search");
+    instruments.retain(|key, instrument| match projection {
+        "symbol-regex" => key.starts_with(symbol.trim_end_matches(".*")),
+        "desc-search" => instrument["description"].as_str().unwrap_or_default().to_lowercase().contains(&symbol.to_lowercase()),
+        _ => *key == symbol,
+    });
+    if projection != "fundamental" {
+        instruments.values_mut().for_each(|instrument| {
+            instrument.as_object_mut().unwrap().remove("fundamental");
+        });
+    }
+    Json(instruments).into_response()
+}
+
+async fn instrument(Path(cusip): Path<String>) -> Response {
+    let instruments = serde_json::from_str::<HashMap<String, Value>>(INSTRUMENTS).unwrap();
+    let mut matches: Vec<Value> = instruments.into_values().filter(|instrument| instrument["cusip"] == cusip.as_str()).collect();
+    matches.iter_mut().for_each(|instrument| {
+        instrument.as_object_mut().unwrap().remove("fundamental");
+    });
+    Json(matches).into_response()
+}
+
+async fn price_history(Path(symbol): Path<String>) -> Response {
+    let mut price_history = serde_json::from_str::<Value>(PRICE_HISTORY).unwrap();
+    price_history["symbol"] = Value::String(symbol);
+    Json(price_history).into_response()
+}
+
+async fn streamer(State(state): State<SharedState>, ws: WebSocketUpgrade) -> Response {
+    ws.on_upgrade(move |socket| stream(state, socket))
+}
+
+fn streamer_response(request: &Value, code: i64, msg: &str) -> Value {
+    let content = json!({"code": code, "msg": msg});
+    json!({"response": [{"service": request["service"], "command": request["command"], "requestid": request["requestid"], "content": content}]})
+}
+
+/// Level-one fields for `symbol`, taken from the quotes fixture when it's there.
+fn streamer_quote(symbol: &str) -> Value {
+    let quotes = serde_json::from_str::<HashMap<String, Value>>(QUOTES).unwrap();
+    let quote = quotes.get(symbol).cloned().unwrap_or_default();
+    json!({"key": symbol, "1": quote["bidPrice"], "2": quote["askPrice"], "3": quote["lastPrice"], "8": quote["totalVolume"], "49": quote["mark"]})
+}
+
+/// Logs in with [`STREAMER_TOKEN`], answers every request with code 0, sends a snapshot for
+/// each symbol on `QUOTE SUBS` and a heartbeat every [`STREAMER_HEARTBEAT_INTERVAL`].
+async fn stream(state: SharedState, mut socket: WebSocket) {
+    let mut events = state.lock().unwrap().streamer_events.subscribe();
+    let mut heartbeat = tokio::time::interval(STREAMER_HEARTBEAT_INTERVAL);
+    loop {
+        let replies = tokio::select! {
+            message = socket.recv() => {
+                let text = match message {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(_)) => continue,
+                    _ => return,
+                };
+                let requests = serde_json::from_str::<Value>(&text).unwrap_or_default();
+                let mut replies = vec![];
+                for request in requests["requests"].as_array().cloned().unwrap_or_default() {
+                    let (service, command) = (request["service"].as_str().unwrap_or_default(), request["command"].as_str().unwrap_or_default());
+                    state.lock().unwrap().streamer_requests.push(format!("{} {}", service, command));
+                    match (service, command) {
+                        ("ADMIN", "LOGIN") if request["parameters"]["token"] != STREAMER_TOKEN => replies.push(streamer_response(&request, 3, "Login denied")),
+                        ("QUOTE", "SUBS") => {
+                            replies.push(streamer_response(&request, 0, "SUBS command succeeded"));
+                            let keys = request["parameters"]["keys"].as_str().unwrap_or_default();
+                            let content: Vec<Value> = keys.split(',').map(streamer_quote).collect();
+                            replies.push(json!({"data": [{"service": "QUOTE", "command": "SUBS", "content": content}]}));
+                        }
+                        _ => replies.push(streamer_response(&request, 0, "")),
+                    }
+                }
+                replies
+            }
+            _ = heartbeat.tick() => {
+                if state.lock().unwrap().streamer_muted {
+                    continue;
+                }
+                vec![json!({"notify": [{"heartbeat": chrono::Utc::now().timestamp_millis().to_string()}]})]
+            }
+            event = events.recv() => match event {
+                Ok(StreamerEvent::Send(message)) => vec![message],
+                _ => return,
+            }
+        };
+        for reply in replies {
+            if socket.send(Message::Text(reply.to_string())).await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MockTda, ACCESS_TOKEN, ACCOUNT_ID, AUTHORIZATION_CODE, REFRESH_TOKEN};
+    use crate::tda_client::{
+        accounts::{GetOrdersQuery, Order, Status, TDAmeritradeClientAccounts},
+        auth::TDAmeritradeClientAuth,
+        error::TdaError,
+        transactions::{GetTransactionsQuery, TDAmeritradeClientTransactions},
+    };
+    use axum::http::StatusCode;
+    use chrono::NaiveDate;
+
+    #[tokio::test]
+    async fn test_accounts_and_transactions() {
+        let tda = MockTda::start();
+        let client = tda.client();
+        let accounts = client.get_accounts(ACCESS_TOKEN).await.unwrap();
+        assert_eq!(accounts.len(), 2);
+        let account = client.get_account(ACCESS_TOKEN, ACCOUNT_ID).await.unwrap();
+        assert_eq!(account.securities_account.account_id(), ACCOUNT_ID);
+        let transactions = client.get_transactions(ACCESS_TOKEN, ACCOUNT_ID, &GetTransactionsQuery::default()).await.unwrap();
+        assert_eq!(transactions.len(), 1);
+        assert!(matches!(
+            client.get_account(ACCESS_TOKEN, "000000000").await,
+            Err(TdaError::Rejected { status: StatusCode::NOT_FOUND, .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_orders() {
+        let tda = MockTda::start();
+        let client = tda.client();
+        let order_ids = |orders: Vec<Order>| orders.into_iter().map(|Order::OrderGet(order)| order.order_id).collect::<Vec<i64>>();
+        let orders = client.get_orders(ACCESS_TOKEN, ACCOUNT_ID, &GetOrdersQuery::default()).await.unwrap();
+        assert_eq!(order_ids(orders), vec![1001, 1002, 1005, 1010, 1012]);
+        let query = GetOrdersQuery {
+            from_entered_time: NaiveDate::from_ymd_opt(2023, 3, 2),
+            to_entered_time: NaiveDate::from_ymd_opt(2023, 3, 3),
+            status: Some(Status::Working),
+            ..GetOrdersQuery::default()
+        };
+        assert_eq!(order_ids(client.get_orders_for_all_accounts(ACCESS_TOKEN, &query).await.unwrap()), vec![1005]);
+        let query = GetOrdersQuery {
+            max_results: Some(2),
+            ..GetOrdersQuery::default()
+        };
+        assert_eq!(order_ids(client.get_orders_for_all_accounts(ACCESS_TOKEN, &query).await.unwrap()), vec![1001, 1002]);
+
+        let Order::OrderGet(order) = client.get_order(ACCESS_TOKEN, "987654321", "1008").await.unwrap();
+        assert_eq!(order.child_order_strategies[0].order_id, 1009);
+        assert!(matches!(
+            client.get_order(ACCESS_TOKEN, ACCOUNT_ID, "1008").await,
+            Err(TdaError::Rejected { status: StatusCode::NOT_FOUND, .. })
+        ));
+        assert_eq!(tda.requests()[..2], ["GET /v1/accounts/123456789/orders", "GET /v1/orders"]);
+    }
+
+    #[tokio::test]
+    async fn test_error_responses() {
+        let tda = MockTda::start();
+        let client = tda.client();
+
+        tda.rate_limit_next(2);
+        assert!(matches!(client.get_accounts(ACCESS_TOKEN).await, Err(TdaError::RateLimited(Some(2)))));
+
+        tda.respond_next(StatusCode::SERVICE_UNAVAILABLE, r#"{"error":"Service unavailable"}"#);
+        assert!(matches!(client.get_accounts(ACCESS_TOKEN).await, Err(TdaError::Upstream { .. })));
+
+        tda.respond_next(StatusCode::OK, r#"{"unexpected":true}"#);
+        assert!(matches!(client.get_accounts(ACCESS_TOKEN).await, Err(TdaError::Decode { .. })));
+
+        tda.expire_access_tokens();
+        assert!(matches!(client.get_accounts(ACCESS_TOKEN).await, Err(TdaError::Unauthorized(_))));
+    }
+
+    #[tokio::test]
+    async fn test_oauth2_token() {
+        let tda = MockTda::start();
+        let client = tda.client();
+        let token = client.exchange_authorization_code_for_token(AUTHORIZATION_CODE).await.unwrap();
+        assert_eq!(token.refresh_token.as_deref(), Some(REFRESH_TOKEN));
+        let token = client.exchange_refresh_token_for_token(REFRESH_TOKEN).await.unwrap();
+        assert!(token.refresh_token.is_none());
+        client.get_accounts(&token.access_token.unwrap()).await.unwrap();
+        assert!(matches!(
+            client.exchange_refresh_token_for_token("revoked").await,
+            Err(TdaError::Rejected { status: StatusCode::BAD_REQUEST, .. })
+        ));
+    }
+}
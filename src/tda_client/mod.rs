@@ -1,13 +1,81 @@
-use reqwest::Client;
-use std::env;
+use error::TdaError;
+use log::{error, warn};
+use reqwest::{
+    header::{LOCATION, RETRY_AFTER},
+    Client, RequestBuilder, Response,
+};
+use serde::de::DeserializeOwned;
+use std::{env, str::FromStr};
 
 pub mod accounts;
 pub mod auth;
+pub mod candle_cache;
+pub mod error;
+pub mod instruments;
+pub mod market_calendar;
+pub mod market_data;
+pub mod market_hours;
+#[cfg(test)]
+pub mod mock;
+pub mod movers;
+pub mod movers_cache;
+pub mod option_chains;
+pub mod orders;
+pub mod price_history;
+pub mod saved_orders;
+pub mod stream_hub;
+pub mod streamer;
+pub mod token_manager;
+pub mod transactions;
+pub mod user_principals;
+pub mod watchlists;
+
+const DEFAULT_BASE_URL: &str = "https://api.tdameritrade.com/v1";
+const DEFAULT_AUTH_URL: &str = "https://auth.tdameritrade.com/auth";
+
+#[derive(Clone, Debug)]
+pub struct TDAmeritradeClientConfig {
+    pub api_key: String,
+    pub base_url: String,
+    pub auth_url: String,
+    pub callback_url: String,
+}
+
+impl TDAmeritradeClientConfig {
+    /// Reads `TDA_API_KEY` and `TDA_API_CALLBACK_URL`, plus the optional `TDA_API_BASE_URL`
+    /// and `TDA_AUTH_URL` overrides used to point the client at a mock server.
+    pub fn from_env() -> Self {
+        Self {
+            api_key: env::var("TDA_API_KEY").expect("TDA_API_KEY not found in .env"),
+            base_url: env::var("TDA_API_BASE_URL").unwrap_or_else(|_| DEFAULT_BASE_URL.to_string()),
+            auth_url: env::var("TDA_AUTH_URL").unwrap_or_else(|_| DEFAULT_AUTH_URL.to_string()),
+            callback_url: env::var("TDA_API_CALLBACK_URL").expect("TDA_API_CALLBACK_URL not found in .env"),
+        }
+    }
+}
+
+/// TDA answers creates with an empty body and points at the new resource through the
+/// `Location` header, e.g. `.../accounts/123/orders/456` for `collection` `orders`.
+/// By then TDA has accepted the request, so a missing or odd header is only logged.
+fn id_from_location<T: FromStr>(response: &Response, collection: &str) -> Option<T> {
+    let location = response.headers().get(LOCATION).and_then(|value| value.to_str().ok()).unwrap_or_default();
+    let mut segments = location.trim_end_matches('/').rsplit('/');
+    let id = match (segments.next(), segments.next()) {
+        (Some(id), Some(parent)) if parent == collection => id.parse::<T>().ok(),
+        _ => None,
+    };
+    if id.is_none() {
+        warn!("No {} id in Location header {:?}", collection, location);
+    }
+    id
+}
 
 #[derive(Clone)]
 pub struct TDAmeritradeClient {
     client: Client,
     base_url: String,
+    auth_url: String,
+    callback_url: String,
     api_key: String,
 }
 
@@ -19,13 +87,48 @@ impl Default for TDAmeritradeClient {
 
 impl TDAmeritradeClient {
     pub fn new() -> Self {
+        TDAmeritradeClient::with_config(TDAmeritradeClientConfig::from_env())
+    }
+
+    pub fn with_config(config: TDAmeritradeClientConfig) -> Self {
         let client = match Client::builder().build() {
             Ok(client) => client,
             Err(e) => panic!("Error building client: {:?}", e),
         };
-        let base_url = "https://api.tdameritrade.com/v1".to_string();
-        let api_key = env::var("TDA_API_KEY").expect("TDA_API_KEY not found in .env");
 
-        TDAmeritradeClient { client, base_url, api_key }
+        TDAmeritradeClient {
+            client,
+            base_url: config.base_url.trim_end_matches('/').to_string(),
+            auth_url: config.auth_url,
+            callback_url: config.callback_url,
+            api_key: config.api_key,
+        }
+    }
+
+    async fn send(&self, request: RequestBuilder) -> Result<Response, TdaError> {
+        let response = request.send().await.map_err(|e| {
+            error!("request error: {}", e);
+            TdaError::from(e)
+        })?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        let path = response.url().path().to_string();
+        let retry_after = response.headers().get(RETRY_AFTER).and_then(|value| value.to_str().ok()).and_then(|value| value.parse::<u64>().ok());
+        let body = response.text().await.unwrap_or_default();
+        let error = TdaError::from_response(status, retry_after, body);
+        error!("{} {}", path, error);
+        Err(error)
+    }
+
+    async fn send_json<T: DeserializeOwned>(&self, request: RequestBuilder) -> Result<T, TdaError> {
+        let response = self.send(request).await?;
+        let path = response.url().path().to_string();
+        let body = response.text().await?;
+        serde_json::from_str::<T>(&body).map_err(|e| {
+            error!("{} decode error: {}", path, e);
+            TdaError::Decode { message: e.to_string(), body }
+        })
     }
 }
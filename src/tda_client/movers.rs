@@ -0,0 +1,107 @@
+use super::{error::TdaError, TDAmeritradeClient};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// The indices TDA reports movers for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum MoversIndex {
+    #[serde(rename = "$COMPX")]
+    Compx,
+    #[serde(rename = "$DJI")]
+    Dji,
+    #[serde(rename = "$SPX.X")]
+    Spx,
+}
+
+impl MoversIndex {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MoversIndex::Compx => "$COMPX",
+            MoversIndex::Dji => "$DJI",
+            MoversIndex::Spx => "$SPX.X",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MoversDirection {
+    Up,
+    Down,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MoversChange {
+    Value,
+    Percent,
+}
+
+/// Without a direction TDA returns gainers and losers together; `change` defaults to `value`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct MoversQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub direction: Option<MoversDirection>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub change: Option<MoversChange>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Mover {
+    pub symbol: String,
+    #[serde(default)]
+    pub description: String,
+    pub direction: MoversDirection,
+    /// In dollars, or as a fraction (`0.05` for 5%) when asked for [`MoversChange::Percent`].
+    pub change: f64,
+    pub last: f64,
+    #[serde(default)]
+    pub total_volume: i64,
+}
+
+#[async_trait]
+pub trait TDAmeritradeClientMovers {
+    async fn get_movers(&self, token: &str, index: MoversIndex, query: &MoversQuery) -> Result<Vec<Mover>, TdaError>;
+}
+
+#[async_trait]
+impl TDAmeritradeClientMovers for TDAmeritradeClient {
+    async fn get_movers(&self, token: &str, index: MoversIndex, query: &MoversQuery) -> Result<Vec<Mover>, TdaError> {
+        let url = format!("{}/marketdata/{}/movers", self.base_url, index.as_str());
+        self.send_json::<Vec<Mover>>(self.client.get(&url).bearer_auth(token).query(query)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Mover, MoversChange, MoversDirection, MoversIndex, MoversQuery, TDAmeritradeClientMovers};
+    use crate::tda_client::mock::{MockTda, ACCESS_TOKEN};
+
+    const MOVERS: &str = include_str!("fixtures/movers.json");
+
+    #[test]
+    fn test_deserialize_movers() {
+        let movers = serde_json::from_str::<Vec<Mover>>(MOVERS).unwrap();
+        assert_eq!(movers.len(), 4);
+        assert_eq!(movers[0].symbol, "NVDA");
+        assert_eq!(movers[0].direction, MoversDirection::Up);
+        assert_eq!(movers[3].direction, MoversDirection::Down);
+        assert_eq!(serde_json::from_str::<MoversIndex>(r#""$SPX.X""#).unwrap(), MoversIndex::Spx);
+    }
+
+    #[tokio::test]
+    async fn test_movers() {
+        let tda = MockTda::start();
+        let client = tda.client();
+        let query = MoversQuery {
+            direction: Some(MoversDirection::Up),
+            change: Some(MoversChange::Percent),
+        };
+        let movers = client.get_movers(ACCESS_TOKEN, MoversIndex::Compx, &query).await.unwrap();
+        assert_eq!(movers.len(), 2);
+        assert!((movers[0].change - 0.0406).abs() < 0.0001);
+        assert_eq!(client.get_movers(ACCESS_TOKEN, MoversIndex::Dji, &MoversQuery::default()).await.unwrap().len(), 4);
+        assert_eq!(tda.requests(), vec!["GET /v1/marketdata/$COMPX/movers", "GET /v1/marketdata/$DJI/movers"]);
+    }
+}
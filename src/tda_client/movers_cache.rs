@@ -0,0 +1,103 @@
+use super::{
+    error::TdaError,
+    movers::{Mover, MoversIndex, MoversQuery},
+};
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tokio::sync::Mutex as AsyncMutex;
+
+const MOVERS_TTL: Duration = Duration::from_secs(60);
+
+type MoversSlot = Arc<AsyncMutex<Option<(Instant, Vec<Mover>)>>>;
+
+/// Movers are the same for every user, so one fetch per index and query serves everyone
+/// until it is [`MOVERS_TTL`] old. Requests arriving during a fetch wait for it.
+#[derive(Clone)]
+pub struct MoversCache {
+    ttl: Duration,
+    slots: Arc<Mutex<HashMap<(MoversIndex, MoversQuery), MoversSlot>>>,
+}
+
+impl Default for MoversCache {
+    fn default() -> Self {
+        MoversCache::new()
+    }
+}
+
+impl MoversCache {
+    pub fn new() -> Self {
+        Self::with_ttl(MOVERS_TTL)
+    }
+
+    pub fn with_ttl(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            slots: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    fn slot(&self, index: MoversIndex, query: MoversQuery) -> MoversSlot {
+        let mut slots = self.slots.lock().unwrap();
+        slots.entry((index, query)).or_default().clone()
+    }
+
+    /// Serves cached movers, calling `fetch` only on a miss so cache hits need no TDA token.
+    pub async fn movers<F, Fut>(&self, index: MoversIndex, query: &MoversQuery, fetch: F) -> Result<Vec<Mover>, TdaError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Vec<Mover>, TdaError>>,
+    {
+        let slot = self.slot(index, *query);
+        let mut cached = slot.lock().await;
+        if let Some((_, movers)) = cached.as_ref().filter(|(fetched_at, _)| fetched_at.elapsed() < self.ttl) {
+            return Ok(movers.clone());
+        }
+        let movers = fetch().await?;
+        *cached = Some((Instant::now(), movers.clone()));
+        Ok(movers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MoversCache;
+    use crate::tda_client::{
+        mock::{MockTda, ACCESS_TOKEN},
+        movers::{MoversChange, MoversDirection, MoversIndex, MoversQuery, TDAmeritradeClientMovers},
+    };
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_refreshes_at_most_once_per_ttl() {
+        let tda = MockTda::start();
+        let client = tda.client();
+        let cache = MoversCache::with_ttl(Duration::from_millis(200));
+        let gainers = MoversQuery {
+            direction: Some(MoversDirection::Up),
+            change: Some(MoversChange::Percent),
+        };
+
+        let fetch = |query: MoversQuery| {
+            let client = client.clone();
+            move || async move { client.get_movers(ACCESS_TOKEN, MoversIndex::Spx, &query).await }
+        };
+
+        let (first, second) = tokio::join!(cache.movers(MoversIndex::Spx, &gainers, fetch(gainers)), cache.movers(MoversIndex::Spx, &gainers, fetch(gainers)));
+        assert_eq!(first.unwrap(), second.unwrap());
+        let losers = MoversQuery {
+            direction: Some(MoversDirection::Down),
+            change: None,
+        };
+        let losers = cache.movers(MoversIndex::Spx, &losers, fetch(losers)).await.unwrap();
+        assert!(losers.iter().all(|mover| mover.direction == MoversDirection::Down));
+        assert_eq!(tda.requests().len(), 2);
+
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        cache.movers(MoversIndex::Spx, &gainers, fetch(gainers)).await.unwrap();
+        assert_eq!(tda.requests().len(), 3);
+    }
+}
@@ -0,0 +1,383 @@
+use super::{accounts::PutCall, error::TdaError, TDAmeritradeClient};
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use serde::{de::IgnoredAny, Deserialize, Deserializer, Serialize};
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ContractType {
+    Call,
+    Put,
+    All,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum OptionStrategy {
+    Single,
+    /// Prices the chain with the query's `volatility`, `underlying_price`, `interest_rate`
+    /// and `days_to_expiration` instead of the market's.
+    Analytical,
+    Covered,
+    Vertical,
+    Calendar,
+    Strangle,
+    Straddle,
+    Butterfly,
+    Condor,
+    Diagonal,
+    Collar,
+    Roll,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub enum StrikeRange {
+    /// In the money
+    #[serde(rename = "ITM")]
+    InTheMoney,
+    /// Near the money
+    #[serde(rename = "NTM")]
+    NearTheMoney,
+    /// Out of the money
+    #[serde(rename = "OTM")]
+    OutOfTheMoney,
+    /// Strikes above market
+    #[serde(rename = "SAK")]
+    StrikesAboveMarket,
+    /// Strikes below market
+    #[serde(rename = "SBK")]
+    StrikesBelowMarket,
+    /// Strikes near market
+    #[serde(rename = "SNK")]
+    StrikesNearMarket,
+    #[serde(rename = "ALL")]
+    All,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OptionChainQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contract_type: Option<ContractType>,
+    /// Number of strikes above and below the at-the-money price.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strike_count: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_quotes: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strategy: Option<OptionStrategy>,
+    /// Strike interval for spread strategies.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interval: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strike: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub range: Option<StrikeRange>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_date: Option<NaiveDate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to_date: Option<NaiveDate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub volatility: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub underlying_price: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interest_rate: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub days_to_expiration: Option<u32>,
+}
+
+/// TDA reports greeks it could not compute as `"NaN"` or `-999.0`; both become `None`.
+fn greek<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<f64>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Value {
+        Number(f64),
+        Other(IgnoredAny),
+    }
+    Ok(match Option::<Value>::deserialize(deserializer)? {
+        Some(Value::Number(value)) if value.is_finite() && value != -999.0 => Some(value),
+        _ => None,
+    })
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Greeks {
+    #[serde(deserialize_with = "greek")]
+    pub delta: Option<f64>,
+    #[serde(deserialize_with = "greek")]
+    pub gamma: Option<f64>,
+    #[serde(deserialize_with = "greek")]
+    pub theta: Option<f64>,
+    #[serde(deserialize_with = "greek")]
+    pub vega: Option<f64>,
+    #[serde(deserialize_with = "greek")]
+    pub rho: Option<f64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OptionContract {
+    pub put_call: PutCall,
+    pub symbol: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub exchange_name: String,
+    #[serde(default)]
+    pub bid: f64,
+    #[serde(default)]
+    pub ask: f64,
+    #[serde(default)]
+    pub last: f64,
+    #[serde(default)]
+    pub mark: f64,
+    #[serde(default)]
+    pub bid_size: i64,
+    #[serde(default)]
+    pub ask_size: i64,
+    #[serde(default)]
+    pub last_size: i64,
+    #[serde(default)]
+    pub high_price: f64,
+    #[serde(default)]
+    pub low_price: f64,
+    #[serde(default)]
+    pub open_price: f64,
+    #[serde(default)]
+    pub close_price: f64,
+    #[serde(default)]
+    pub total_volume: i64,
+    pub quote_time_in_long: Option<i64>,
+    pub trade_time_in_long: Option<i64>,
+    #[serde(default)]
+    pub net_change: f64,
+    #[serde(default, deserialize_with = "greek")]
+    pub volatility: Option<f64>,
+    #[serde(flatten)]
+    pub greeks: Greeks,
+    #[serde(default)]
+    pub open_interest: i64,
+    #[serde(default, deserialize_with = "greek")]
+    pub time_value: Option<f64>,
+    #[serde(default, deserialize_with = "greek")]
+    pub theoretical_option_value: Option<f64>,
+    #[serde(default, deserialize_with = "greek")]
+    pub theoretical_volatility: Option<f64>,
+    pub strike_price: f64,
+    /// Expiration in epoch milliseconds.
+    pub expiration_date: i64,
+    pub days_to_expiration: i64,
+    #[serde(default = "default_multiplier")]
+    pub multiplier: f64,
+    #[serde(default)]
+    pub in_the_money: bool,
+    #[serde(default)]
+    pub non_standard: bool,
+    #[serde(default)]
+    pub mini: bool,
+}
+
+fn default_multiplier() -> f64 {
+    100.0
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Underlying {
+    pub symbol: String,
+    pub description: String,
+    pub bid: f64,
+    pub ask: f64,
+    pub last: f64,
+    pub mark: f64,
+    pub close: f64,
+    pub change: f64,
+    pub percent_change: f64,
+    pub total_volume: i64,
+    pub quote_time: i64,
+    pub trade_time: i64,
+    pub delayed: bool,
+}
+
+/// Contracts keyed by expiration (`"2024-01-19:30"`, date and days to expiration) and then
+/// by strike (`"150.0"`), as TDA returns them.
+pub type ExpirationDateMap = BTreeMap<String, BTreeMap<String, Vec<OptionContract>>>;
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct OptionChain {
+    pub symbol: String,
+    pub status: String,
+    pub underlying: Option<Underlying>,
+    pub strategy: Option<OptionStrategy>,
+    pub interval: f64,
+    pub is_delayed: bool,
+    pub is_index: bool,
+    pub interest_rate: f64,
+    pub underlying_price: f64,
+    pub volatility: f64,
+    pub days_to_expiration: f64,
+    pub number_of_contracts: i64,
+    pub call_exp_date_map: ExpirationDateMap,
+    pub put_exp_date_map: ExpirationDateMap,
+}
+
+impl OptionChain {
+    /// Every call and then every put in the chain, flattened out of the expiration maps.
+    pub fn contracts(&self) -> impl Iterator<Item = &OptionContract> {
+        self.call_exp_date_map.values().chain(self.put_exp_date_map.values()).flat_map(|strikes| strikes.values()).flatten()
+    }
+}
+
+/// A TDA option symbol such as `AAPL_012024C150`: underlying, expiration as `MMDDYY`,
+/// `C` or `P`, and the strike without trailing zeros.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OptionSymbol {
+    pub underlying: String,
+    pub expiration: NaiveDate,
+    pub put_call: PutCall,
+    pub strike: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseOptionSymbolError(String);
+
+impl fmt::Display for ParseOptionSymbolError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid option symbol: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseOptionSymbolError {}
+
+impl FromStr for OptionSymbol {
+    type Err = ParseOptionSymbolError;
+
+    fn from_str(symbol: &str) -> Result<Self, Self::Err> {
+        let error = || ParseOptionSymbolError(symbol.to_string());
+        let (underlying, contract) = symbol.rsplit_once('_').ok_or_else(error)?;
+        if underlying.is_empty() || contract.len() < 8 || !contract.is_char_boundary(6) {
+            return Err(error());
+        }
+        let (expiration, rest) = contract.split_at(6);
+        let expiration = NaiveDate::parse_from_str(expiration, "%m%d%y").map_err(|_| error())?;
+        let (put_call, strike) = match (rest.strip_prefix('C'), rest.strip_prefix('P')) {
+            (Some(strike), _) => (PutCall::Call, strike),
+            (_, Some(strike)) => (PutCall::Put, strike),
+            _ => return Err(error()),
+        };
+        let strike = strike.parse::<f64>().ok().filter(|strike| strike.is_finite() && *strike > 0.0).ok_or_else(error)?;
+        Ok(Self {
+            underlying: underlying.to_string(),
+            expiration,
+            put_call,
+            strike,
+        })
+    }
+}
+
+impl fmt::Display for OptionSymbol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let put_call = match self.put_call {
+            PutCall::Call => "C",
+            PutCall::Put => "P",
+        };
+        write!(f, "{}_{}{}{}", self.underlying, self.expiration.format("%m%d%y"), put_call, self.strike)
+    }
+}
+
+#[async_trait]
+pub trait TDAmeritradeClientOptionChains {
+    async fn get_option_chain(&self, token: &str, symbol: &str, query: &OptionChainQuery) -> Result<OptionChain, TdaError>;
+}
+
+#[async_trait]
+impl TDAmeritradeClientOptionChains for TDAmeritradeClient {
+    async fn get_option_chain(&self, token: &str, symbol: &str, query: &OptionChainQuery) -> Result<OptionChain, TdaError> {
+        let url = format!("{}/marketdata/chains", self.base_url);
+        self.send_json::<OptionChain>(self.client.get(&url).bearer_auth(token).query(&[("symbol", symbol)]).query(query)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ContractType, OptionChain, OptionChainQuery, OptionStrategy, OptionSymbol, StrikeRange, TDAmeritradeClientOptionChains};
+    use crate::tda_client::{
+        accounts::PutCall,
+        mock::{MockTda, ACCESS_TOKEN},
+    };
+    use chrono::NaiveDate;
+
+    const CHAINS: &str = include_str!("fixtures/chains.json");
+
+    #[test]
+    fn test_parse_option_symbol() {
+        let symbol = "AAPL_012024C150".parse::<OptionSymbol>().unwrap();
+        assert_eq!(symbol.underlying, "AAPL");
+        assert_eq!(symbol.expiration, NaiveDate::from_ymd_opt(2024, 1, 20).unwrap());
+        assert_eq!(symbol.put_call, PutCall::Call);
+        assert_eq!(symbol.strike, 150.0);
+        assert_eq!(symbol.to_string(), "AAPL_012024C150");
+
+        let symbol = "BRK.B_031723P312.5".parse::<OptionSymbol>().unwrap();
+        assert_eq!(symbol.underlying, "BRK.B");
+        assert_eq!(symbol.put_call, PutCall::Put);
+        assert_eq!(symbol.strike, 312.5);
+        assert_eq!(symbol.to_string(), "BRK.B_031723P312.5");
+
+        for invalid in ["AAPL", "_012024C150", "AAPL_013224C150", "AAPL_012024X150", "AAPL_012024C", "AAPL_012024Cabc", "AAPL_012024C-5"] {
+            assert!(invalid.parse::<OptionSymbol>().is_err(), "{} should not parse", invalid);
+        }
+    }
+
+    #[test]
+    fn test_serialize_option_chain_query() {
+        let query = OptionChainQuery {
+            contract_type: Some(ContractType::Call),
+            strike_count: Some(4),
+            strategy: Some(OptionStrategy::Analytical),
+            range: Some(StrikeRange::OutOfTheMoney),
+            from_date: NaiveDate::from_ymd_opt(2023, 3, 1),
+            to_date: NaiveDate::from_ymd_opt(2023, 3, 31),
+            volatility: Some(29.5),
+            ..OptionChainQuery::default()
+        };
+        let request = reqwest::Client::new().get("http://localhost/chains").query(&[("symbol", "AAPL")]).query(&query).build().unwrap();
+        assert_eq!(
+            request.url().query(),
+            Some("symbol=AAPL&contractType=CALL&strikeCount=4&strategy=ANALYTICAL&range=OTM&fromDate=2023-03-01&toDate=2023-03-31&volatility=29.5")
+        );
+    }
+
+    #[test]
+    fn test_deserialize_option_chain() {
+        let chain = serde_json::from_str::<OptionChain>(CHAINS).unwrap();
+        assert_eq!(chain.symbol, "AAPL");
+        assert_eq!(chain.underlying.as_ref().unwrap().mark, 151.03);
+        assert_eq!(chain.contracts().count(), 4);
+
+        let call = &chain.call_exp_date_map["2023-03-17:14"]["150.0"][0];
+        assert_eq!(call.put_call, PutCall::Call);
+        assert_eq!(call.greeks.delta, Some(0.56));
+        assert_eq!(call.symbol.parse::<OptionSymbol>().unwrap().strike, call.strike_price);
+
+        let put = &chain.put_exp_date_map["2023-03-17:14"]["155.0"][0];
+        assert_eq!(put.greeks.delta, None);
+        assert_eq!(put.greeks.gamma, None);
+        assert_eq!(put.volatility, None);
+    }
+
+    #[tokio::test]
+    async fn test_option_chain() {
+        let tda = MockTda::start();
+        let client = tda.client();
+        let chain = client.get_option_chain(ACCESS_TOKEN, "AAPL", &OptionChainQuery::default()).await.unwrap();
+        assert_eq!(chain.contracts().count(), 4);
+        let chain = client.get_option_chain(ACCESS_TOKEN, "NOPE", &OptionChainQuery::default()).await.unwrap();
+        assert_eq!(chain.status, "FAILED");
+        assert_eq!(chain.contracts().count(), 0);
+    }
+}
@@ -0,0 +1,229 @@
+use super::{
+    accounts::{ComplexOrderStrategyType, Duration, Instruction, Instrument, OrderLeg, OrderStrategyType, OrderType, Session, StopType},
+    error::TdaError,
+    id_from_location, TDAmeritradeClient,
+};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session: Option<Session>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration: Option<Duration>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order_type: Option<OrderType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub complex_order_strategy_type: Option<ComplexOrderStrategyType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_price: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_type: Option<StopType>,
+    pub order_strategy_type: OrderStrategyType,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub order_leg_collection: Vec<OrderLeg>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub child_order_strategies: Vec<OrderRequest>,
+}
+
+impl OrderRequest {
+    pub fn builder(order_type: OrderType) -> OrderRequestBuilder {
+        OrderRequestBuilder::new(order_type)
+    }
+
+    pub fn one_cancels_other(first: OrderRequest, second: OrderRequest) -> OrderRequest {
+        OrderRequest {
+            session: None,
+            duration: None,
+            order_type: None,
+            complex_order_strategy_type: None,
+            price: None,
+            stop_price: None,
+            stop_type: None,
+            order_strategy_type: OrderStrategyType::Oco,
+            order_leg_collection: vec![],
+            child_order_strategies: vec![first, second],
+        }
+    }
+}
+
+pub struct OrderRequestBuilder {
+    request: OrderRequest,
+}
+
+impl OrderRequestBuilder {
+    pub fn new(order_type: OrderType) -> Self {
+        let request = OrderRequest {
+            session: Some(Session::Normal),
+            duration: Some(Duration::Day),
+            order_type: Some(order_type),
+            complex_order_strategy_type: None,
+            price: None,
+            stop_price: None,
+            stop_type: None,
+            order_strategy_type: OrderStrategyType::Single,
+            order_leg_collection: vec![],
+            child_order_strategies: vec![],
+        };
+        Self { request }
+    }
+
+    pub fn session(mut self, session: Session) -> Self {
+        self.request.session = Some(session);
+        self
+    }
+
+    pub fn duration(mut self, duration: Duration) -> Self {
+        self.request.duration = Some(duration);
+        self
+    }
+
+    pub fn price(mut self, price: f64) -> Self {
+        self.request.price = Some(price);
+        self
+    }
+
+    pub fn stop_price(mut self, stop_price: f64) -> Self {
+        self.request.stop_price = Some(stop_price);
+        self
+    }
+
+    pub fn stop_type(mut self, stop_type: StopType) -> Self {
+        self.request.stop_type = Some(stop_type);
+        self
+    }
+
+    pub fn complex_order_strategy_type(mut self, complex_order_strategy_type: ComplexOrderStrategyType) -> Self {
+        self.request.complex_order_strategy_type = Some(complex_order_strategy_type);
+        self
+    }
+
+    pub fn order_strategy_type(mut self, order_strategy_type: OrderStrategyType) -> Self {
+        self.request.order_strategy_type = order_strategy_type;
+        self
+    }
+
+    pub fn leg(mut self, instruction: Instruction, quantity: f64, instrument: Instrument) -> Self {
+        self.request.order_leg_collection.push(OrderLeg::new(instruction, quantity, instrument));
+        self
+    }
+
+    pub fn child_order_strategy(mut self, child_order_strategy: OrderRequest) -> Self {
+        self.request.child_order_strategies.push(child_order_strategy);
+        self
+    }
+
+    pub fn build(self) -> OrderRequest {
+        self.request
+    }
+}
+
+#[async_trait]
+pub trait TDAmeritradeClientOrders {
+    /// Returns the new order's id, if TDA said what it is.
+    async fn place_order(&self, token: &str, account_id: &str, order: &OrderRequest) -> Result<Option<i64>, TdaError>;
+    /// Replacing cancels the order and places a new one; returns the new order's id, if known.
+    async fn replace_order(&self, token: &str, account_id: &str, order_id: &str, order: &OrderRequest) -> Result<Option<i64>, TdaError>;
+    async fn cancel_order(&self, token: &str, account_id: &str, order_id: &str) -> Result<(), TdaError>;
+}
+
+#[async_trait]
+impl TDAmeritradeClientOrders for TDAmeritradeClient {
+    async fn place_order(&self, token: &str, account_id: &str, order: &OrderRequest) -> Result<Option<i64>, TdaError> {
+        let url = format!("{}/accounts/{}/orders", self.base_url, account_id);
+        let response = self.send(self.client.post(&url).bearer_auth(token).json(order)).await?;
+        Ok(id_from_location(&response, "orders"))
+    }
+
+    async fn replace_order(&self, token: &str, account_id: &str, order_id: &str, order: &OrderRequest) -> Result<Option<i64>, TdaError> {
+        let url = format!("{}/accounts/{}/orders/{}", self.base_url, account_id, order_id);
+        let response = self.send(self.client.put(&url).bearer_auth(token).json(order)).await?;
+        Ok(id_from_location(&response, "orders"))
+    }
+
+    async fn cancel_order(&self, token: &str, account_id: &str, order_id: &str) -> Result<(), TdaError> {
+        let url = format!("{}/accounts/{}/orders/{}", self.base_url, account_id, order_id);
+        self.send(self.client.delete(&url).bearer_auth(token)).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OrderRequest, TDAmeritradeClientOrders};
+    use crate::tda_client::{
+        accounts::{Duration, Instruction, Instrument, OrderStrategyType, OrderType},
+        mock::{MockTda, ACCESS_TOKEN, ACCOUNT_ID, PLACED_ORDER_ID},
+    };
+    use axum::http::StatusCode;
+    use serde_json::json;
+
+    #[test]
+    fn test_build_limit_order() {
+        let order = OrderRequest::builder(OrderType::Limit)
+            .duration(Duration::GoodTillCancel)
+            .price(142.5)
+            .leg(Instruction::Buy, 10.0, Instrument::equity("AAPL"))
+            .build();
+        assert_eq!(
+            serde_json::to_value(&order).unwrap(),
+            json!({
+                "session": "NORMAL",
+                "duration": "GOOD_TILL_CANCEL",
+                "orderType": "LIMIT",
+                "price": 142.5,
+                "orderStrategyType": "SINGLE",
+                "orderLegCollection": [
+                    { "instrument": { "assetType": "EQUITY", "symbol": "AAPL" }, "instruction": "BUY", "quantity": 10.0 }
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn test_build_one_triggers_oco_order() {
+        let take_profit = OrderRequest::builder(OrderType::Limit).price(160.0).leg(Instruction::Sell, 10.0, Instrument::equity("AAPL")).build();
+        let stop_loss = OrderRequest::builder(OrderType::Stop)
+            .stop_price(130.0)
+            .leg(Instruction::Sell, 10.0, Instrument::equity("AAPL"))
+            .build();
+        let bracket = OrderRequest::one_cancels_other(take_profit, stop_loss);
+        let order = OrderRequest::builder(OrderType::Limit)
+            .price(142.5)
+            .order_strategy_type(OrderStrategyType::Trigger)
+            .leg(Instruction::Buy, 10.0, Instrument::equity("AAPL"))
+            .child_order_strategy(bracket)
+            .build();
+        let value = serde_json::to_value(&order).unwrap();
+        assert_eq!(value["childOrderStrategies"][0]["orderStrategyType"], "OCO");
+        assert_eq!(value["childOrderStrategies"][0]["childOrderStrategies"][1]["stopPrice"], 130.0);
+        assert!(value["childOrderStrategies"][0].get("orderType").is_none());
+        assert!(value["childOrderStrategies"][0].get("orderLegCollection").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_place_replace_and_cancel_order() {
+        let tda = MockTda::start();
+        let client = tda.client();
+        let order = OrderRequest::builder(OrderType::Market).leg(Instruction::Buy, 1.0, Instrument::equity("AAPL")).build();
+        assert_eq!(client.place_order(ACCESS_TOKEN, ACCOUNT_ID, &order).await.unwrap(), Some(PLACED_ORDER_ID));
+        assert_eq!(client.replace_order(ACCESS_TOKEN, ACCOUNT_ID, "1003", &order).await.unwrap(), Some(PLACED_ORDER_ID + 1));
+        client.cancel_order(ACCESS_TOKEN, ACCOUNT_ID, "1004").await.unwrap();
+        assert_eq!(tda.requests().last().unwrap(), "DELETE /v1/accounts/123456789/orders/1004");
+    }
+
+    #[tokio::test]
+    async fn test_accepted_order_without_location() {
+        let tda = MockTda::start();
+        let client = tda.client();
+        let order = OrderRequest::builder(OrderType::Market).leg(Instruction::Buy, 1.0, Instrument::equity("AAPL")).build();
+        tda.respond_next(StatusCode::CREATED, "");
+        assert_eq!(client.place_order(ACCESS_TOKEN, ACCOUNT_ID, &order).await.unwrap(), None);
+        tda.respond_next(StatusCode::CREATED, "");
+        assert_eq!(client.replace_order(ACCESS_TOKEN, ACCOUNT_ID, "1003", &order).await.unwrap(), None);
+    }
+}
@@ -0,0 +1,127 @@
+use super::{error::TdaError, TDAmeritradeClient};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PeriodType {
+    Day,
+    Month,
+    Year,
+    Ytd,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FrequencyType {
+    Minute,
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+impl FrequencyType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FrequencyType::Minute => "minute",
+            FrequencyType::Daily => "daily",
+            FrequencyType::Weekly => "weekly",
+            FrequencyType::Monthly => "monthly",
+        }
+    }
+
+    /// Length of one bar in milliseconds, rounded up for months.
+    pub fn bar_millis(&self, frequency: u32) -> i64 {
+        let minute = 60 * 1000;
+        let day = 24 * 60 * minute;
+        match self {
+            FrequencyType::Minute => frequency as i64 * minute,
+            FrequencyType::Daily => frequency as i64 * day,
+            FrequencyType::Weekly => frequency as i64 * 7 * day,
+            FrequencyType::Monthly => frequency as i64 * 31 * day,
+        }
+    }
+}
+
+/// Either `period_type`/`period` or `start_date`/`end_date` (epoch milliseconds) select the range.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceHistoryQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub period_type: Option<PeriodType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub period: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frequency_type: Option<FrequencyType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frequency: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_date: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_date: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub need_extended_hours_data: Option<bool>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Candle {
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: i64,
+    /// Bar open time in epoch milliseconds.
+    pub datetime: i64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PriceHistory {
+    pub symbol: String,
+    pub candles: Vec<Candle>,
+    pub empty: bool,
+}
+
+#[async_trait]
+pub trait TDAmeritradeClientPriceHistory {
+    async fn get_price_history(&self, token: &str, symbol: &str, query: &PriceHistoryQuery) -> Result<PriceHistory, TdaError>;
+}
+
+#[async_trait]
+impl TDAmeritradeClientPriceHistory for TDAmeritradeClient {
+    async fn get_price_history(&self, token: &str, symbol: &str, query: &PriceHistoryQuery) -> Result<PriceHistory, TdaError> {
+        let url = format!("{}/marketdata/{}/pricehistory", self.base_url, symbol);
+        self.send_json::<PriceHistory>(self.client.get(&url).bearer_auth(token).query(query)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FrequencyType, PriceHistory, PriceHistoryQuery};
+
+    #[test]
+    fn test_serialize_price_history_query() {
+        let query = PriceHistoryQuery {
+            frequency_type: Some(FrequencyType::Minute),
+            frequency: Some(5),
+            start_date: Some(1677679200000),
+            end_date: Some(1677704400000),
+            need_extended_hours_data: Some(false),
+            ..PriceHistoryQuery::default()
+        };
+        let request = reqwest::Client::new().get("http://localhost/pricehistory").query(&query).build().unwrap();
+        assert_eq!(
+            request.url().query(),
+            Some("frequencyType=minute&frequency=5&startDate=1677679200000&endDate=1677704400000&needExtendedHoursData=false")
+        );
+    }
+
+    #[test]
+    fn test_deserialize_price_history() {
+        let json = r#"{"candles":[{"open":142.0,"high":142.4,"low":141.8,"close":142.3,"volume":812345,"datetime":1677679200000}],"symbol":"AAPL","empty":false}"#;
+        let history = serde_json::from_str::<PriceHistory>(json).unwrap();
+        assert_eq!(history.candles[0].close, 142.3);
+        assert_eq!(history.candles[0].datetime, 1677679200000);
+    }
+}
@@ -0,0 +1,116 @@
+use super::{error::TdaError, id_from_location, orders::OrderRequest, TDAmeritradeClient};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// An order staged in TDA without being sent to the market.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedOrder {
+    pub saved_order_id: i64,
+    #[serde(default)]
+    pub saved_time: String,
+    #[serde(default)]
+    pub account_id: Option<i64>,
+    #[serde(flatten)]
+    pub order: OrderRequest,
+}
+
+#[async_trait]
+pub trait TDAmeritradeClientSavedOrders {
+    async fn create_saved_order(&self, token: &str, account_id: &str, order: &OrderRequest) -> Result<Option<i64>, TdaError>;
+    async fn get_saved_orders(&self, token: &str, account_id: &str) -> Result<Vec<SavedOrder>, TdaError>;
+    async fn get_saved_order(&self, token: &str, account_id: &str, saved_order_id: &str) -> Result<SavedOrder, TdaError>;
+    async fn replace_saved_order(&self, token: &str, account_id: &str, saved_order_id: &str, order: &OrderRequest) -> Result<(), TdaError>;
+    async fn delete_saved_order(&self, token: &str, account_id: &str, saved_order_id: &str) -> Result<(), TdaError>;
+}
+
+#[async_trait]
+impl TDAmeritradeClientSavedOrders for TDAmeritradeClient {
+    async fn create_saved_order(&self, token: &str, account_id: &str, order: &OrderRequest) -> Result<Option<i64>, TdaError> {
+        let url = format!("{}/accounts/{}/savedorders", self.base_url, account_id);
+        let response = self.send(self.client.post(&url).bearer_auth(token).json(order)).await?;
+        Ok(id_from_location(&response, "savedorders"))
+    }
+
+    async fn get_saved_orders(&self, token: &str, account_id: &str) -> Result<Vec<SavedOrder>, TdaError> {
+        let url = format!("{}/accounts/{}/savedorders", self.base_url, account_id);
+        self.send_json::<Vec<SavedOrder>>(self.client.get(&url).bearer_auth(token)).await
+    }
+
+    async fn get_saved_order(&self, token: &str, account_id: &str, saved_order_id: &str) -> Result<SavedOrder, TdaError> {
+        let url = format!("{}/accounts/{}/savedorders/{}", self.base_url, account_id, saved_order_id);
+        self.send_json::<SavedOrder>(self.client.get(&url).bearer_auth(token)).await
+    }
+
+    /// Unlike replacing a live order, the saved order keeps its id.
+    async fn replace_saved_order(&self, token: &str, account_id: &str, saved_order_id: &str, order: &OrderRequest) -> Result<(), TdaError> {
+        let url = format!("{}/accounts/{}/savedorders/{}", self.base_url, account_id, saved_order_id);
+        self.send(self.client.put(&url).bearer_auth(token).json(order)).await?;
+        Ok(())
+    }
+
+    async fn delete_saved_order(&self, token: &str, account_id: &str, saved_order_id: &str) -> Result<(), TdaError> {
+        let url = format!("{}/accounts/{}/savedorders/{}", self.base_url, account_id, saved_order_id);
+        self.send(self.client.delete(&url).bearer_auth(token)).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SavedOrder, TDAmeritradeClientSavedOrders};
+    use crate::tda_client::{
+        accounts::{Duration, Instruction, Instrument, OrderType},
+        error::TdaError,
+        mock::{MockTda, ACCESS_TOKEN, ACCOUNT_ID, CREATED_SAVED_ORDER_ID},
+        orders::OrderRequest,
+    };
+    use axum::http::StatusCode;
+
+    const SAVED_ORDERS: &str = include_str!("fixtures/saved_orders.json");
+
+    #[test]
+    fn test_deserialize_saved_orders() {
+        let saved_orders = serde_json::from_str::<Vec<SavedOrder>>(SAVED_ORDERS).unwrap();
+        assert_eq!(saved_orders.len(), 2);
+        let saved_order = &saved_orders[0];
+        assert_eq!(saved_order.saved_order_id, 5001);
+        assert_eq!(saved_order.order.order_type, Some(OrderType::Limit));
+        assert_eq!(saved_order.order.duration, Some(Duration::GoodTillCancel));
+        assert_eq!(saved_order.order.order_leg_collection[0].instruction, Instruction::Buy);
+
+        let value = serde_json::to_value(saved_order).unwrap();
+        assert_eq!(value["savedOrderId"], 5001);
+        assert_eq!(value["orderLegCollection"][0]["instrument"]["symbol"], "MSFT");
+    }
+
+    #[tokio::test]
+    async fn test_saved_orders() {
+        let tda = MockTda::start();
+        let client = tda.client();
+        let saved_orders = client.get_saved_orders(ACCESS_TOKEN, ACCOUNT_ID).await.unwrap();
+        assert_eq!(saved_orders.len(), 1);
+        let saved_order = client.get_saved_order(ACCESS_TOKEN, ACCOUNT_ID, "5001").await.unwrap();
+        assert_eq!(saved_order, saved_orders[0]);
+        assert!(matches!(
+            client.get_saved_order(ACCESS_TOKEN, ACCOUNT_ID, "5002").await,
+            Err(TdaError::Rejected { status: StatusCode::NOT_FOUND, .. })
+        ));
+
+        let order = OrderRequest::builder(OrderType::Limit).price(250.0).leg(Instruction::Buy, 5.0, Instrument::equity("MSFT")).build();
+        assert_eq!(client.create_saved_order(ACCESS_TOKEN, ACCOUNT_ID, &order).await.unwrap(), Some(CREATED_SAVED_ORDER_ID));
+        client.replace_saved_order(ACCESS_TOKEN, ACCOUNT_ID, "5003", &order).await.unwrap();
+        client.delete_saved_order(ACCESS_TOKEN, ACCOUNT_ID, "5003").await.unwrap();
+        let requests = tda.requests();
+        assert_eq!(
+            requests[requests.len() - 3..],
+            [
+                "POST /v1/accounts/123456789/savedorders",
+                "PUT /v1/accounts/123456789/savedorders/5003",
+                "DELETE /v1/accounts/123456789/savedorders/5003"
+            ]
+        );
+        tda.respond_next(StatusCode::CREATED, "");
+        assert_eq!(client.create_saved_order(ACCESS_TOKEN, ACCOUNT_ID, &order).await.unwrap(), None);
+    }
+}
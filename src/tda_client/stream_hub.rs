@@ -0,0 +1,255 @@
+use super::{
+    streamer::{self, StreamUpdate, StreamerConfig},
+    token_manager::TokenManager,
+    TDAmeritradeClient,
+};
+use log::warn;
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, Weak,
+    },
+};
+use tokio::sync::{broadcast, watch};
+use uuid::Uuid;
+
+/// Updates buffered per user before slow clients start missing them.
+const UPDATES_CAPACITY: usize = 1024;
+
+/// Shares one TDA streamer session per user between all of that user's browser clients.
+/// The session starts with the first [`StreamClient`] and logs out once the last is dropped.
+#[derive(Clone)]
+pub struct StreamHub {
+    tda_client: TDAmeritradeClient,
+    token_manager: TokenManager,
+    config: StreamerConfig,
+    streams: Arc<Mutex<HashMap<Uuid, Weak<UserStream>>>>,
+}
+
+struct UserStream {
+    updates: broadcast::Sender<StreamUpdate>,
+    symbols: watch::Sender<BTreeSet<String>>,
+    /// How many clients want each symbol.
+    subscribers: Mutex<HashMap<String, usize>>,
+    connected: Arc<AtomicBool>,
+}
+
+impl UserStream {
+    fn update_symbols(&self, f: impl FnOnce(&mut HashMap<String, usize>)) {
+        let mut subscribers = self.subscribers.lock().unwrap();
+        f(&mut subscribers);
+        subscribers.retain(|_, count| *count > 0);
+        let symbols: BTreeSet<String> = subscribers.keys().cloned().collect();
+        self.symbols.send_if_modified(|current| {
+            let modified = *current != symbols;
+            *current = symbols;
+            modified
+        });
+    }
+}
+
+impl StreamHub {
+    pub fn new(tda_client: TDAmeritradeClient, token_manager: TokenManager) -> Self {
+        Self::with_config(tda_client, token_manager, StreamerConfig::default())
+    }
+
+    pub fn with_config(tda_client: TDAmeritradeClient, token_manager: TokenManager, config: StreamerConfig) -> Self {
+        Self {
+            tda_client,
+            token_manager,
+            config,
+            streams: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub fn connect(&self, user_id: Uuid) -> StreamClient {
+        let mut streams = self.streams.lock().unwrap();
+        if let Some(stream) = streams.get(&user_id).and_then(Weak::upgrade) {
+            return StreamClient::new(stream);
+        }
+        streams.retain(|_, stream| stream.strong_count() > 0);
+
+        let (symbols, symbols_rx) = watch::channel(BTreeSet::new());
+        let (updates, _) = broadcast::channel(UPDATES_CAPACITY);
+        let connected = Arc::new(AtomicBool::new(false));
+        let stream = Arc::new(UserStream {
+            updates: updates.clone(),
+            symbols,
+            subscribers: Mutex::new(HashMap::new()),
+            connected: connected.clone(),
+        });
+        streams.insert(user_id, Arc::downgrade(&stream));
+        // Subscribe before the session starts so the client sees its first status update.
+        let client = StreamClient::new(stream);
+        tokio::spawn(streamer::run(
+            self.tda_client.clone(),
+            self.token_manager.clone(),
+            user_id,
+            self.config.clone(),
+            symbols_rx,
+            updates,
+            connected,
+        ));
+        client
+    }
+}
+
+/// One browser client's view of its user's stream: every account activity update, plus
+/// quotes for the symbols this client subscribed to.
+pub struct StreamClient {
+    stream: Arc<UserStream>,
+    updates: broadcast::Receiver<StreamUpdate>,
+    symbols: BTreeSet<String>,
+}
+
+impl StreamClient {
+    fn new(stream: Arc<UserStream>) -> Self {
+        let updates = stream.updates.subscribe();
+        Self {
+            stream,
+            updates,
+            symbols: BTreeSet::new(),
+        }
+    }
+
+    pub fn symbols(&self) -> &BTreeSet<String> {
+        &self.symbols
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.stream.connected.load(Ordering::SeqCst)
+    }
+
+    pub fn subscribe(&mut self, symbols: &[String]) {
+        let added: Vec<String> = symbols.iter().filter(|symbol| self.symbols.insert(symbol.to_string())).cloned().collect();
+        self.stream
+            .update_symbols(|subscribers| added.into_iter().for_each(|symbol| *subscribers.entry(symbol).or_default() += 1));
+    }
+
+    pub fn unsubscribe(&mut self, symbols: &[String]) {
+        let removed: Vec<String> = symbols.iter().filter(|symbol| self.symbols.remove(*symbol)).cloned().collect();
+        self.stream.update_symbols(|subscribers| {
+            for symbol in &removed {
+                if let Some(count) = subscribers.get_mut(symbol) {
+                    *count -= 1;
+                }
+            }
+        });
+    }
+
+    /// Waits for the next update meant for this client. Updates missed because the client
+    /// fell too far behind are skipped; the next quote for a symbol carries its latest values.
+    pub async fn recv(&mut self) -> Option<StreamUpdate> {
+        loop {
+            match self.updates.recv().await {
+                Ok(StreamUpdate::Quote(quote)) if !self.symbols.contains(&quote.symbol) => continue,
+                Ok(update) => return Some(update),
+                Err(broadcast::error::RecvError::Lagged(skipped)) => warn!("Stream client skipped {} updates", skipped),
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+impl Drop for StreamClient {
+    fn drop(&mut self) {
+        let symbols: Vec<String> = self.symbols.iter().cloned().collect();
+        self.unsubscribe(&symbols);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StreamHub;
+    use crate::{
+        database_client::memory::InMemoryDatabase,
+        tda_client::{
+            mock::{token_response, MockTda},
+            streamer::{StreamUpdate, StreamerConfig},
+            token_manager::TokenManager,
+        },
+    };
+    use std::{sync::Arc, time::Duration};
+    use tokio::time::timeout;
+    use uuid::Uuid;
+
+    async fn hub(tda: &MockTda) -> (StreamHub, Uuid) {
+        let token_manager = TokenManager::new(tda.client(), Arc::new(InMemoryDatabase::default()));
+        let user_id = Uuid::new_v4();
+        token_manager.store(user_id, &token_response()).await.unwrap();
+        let config = StreamerConfig {
+            heartbeat_timeout: Duration::from_millis(500),
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(50),
+        };
+        (StreamHub::with_config(tda.client(), token_manager, config), user_id)
+    }
+
+    async fn next(client: &mut super::StreamClient) -> StreamUpdate {
+        timeout(Duration::from_secs(5), client.recv()).await.expect("no stream update").unwrap()
+    }
+
+    fn logins(tda: &MockTda) -> usize {
+        tda.streamer_requests().iter().filter(|request| *request == "ADMIN LOGIN").count()
+    }
+
+    #[tokio::test]
+    async fn test_quotes_and_account_activity() {
+        let tda = MockTda::start();
+        let (hub, user_id) = hub(&tda).await;
+        let mut client = hub.connect(user_id);
+        let mut other = hub.connect(user_id);
+        assert_eq!(next(&mut client).await, StreamUpdate::Status { connected: true });
+        assert!(client.is_connected());
+
+        client.subscribe(&["AAPL".to_string()]);
+        match next(&mut client).await {
+            StreamUpdate::Quote(quote) => assert_eq!(quote.symbol, "AAPL"),
+            update => panic!("unexpected update {:?}", update),
+        }
+
+        tda.push_account_activity("OrderFill", "<OrderFillMessage/>");
+        assert!(matches!(next(&mut client).await, StreamUpdate::AccountActivity(activity) if activity.message_type == "OrderFill"));
+        // The other client shares the session but not the AAPL subscription.
+        assert_eq!(next(&mut other).await, StreamUpdate::Status { connected: true });
+        assert!(matches!(next(&mut other).await, StreamUpdate::AccountActivity(_)));
+        assert_eq!(logins(&tda), 1);
+
+        drop(client);
+        drop(other);
+        timeout(Duration::from_secs(5), async {
+            while !tda.streamer_requests().contains(&"ADMIN LOGOUT".to_string()) {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("no logout");
+        assert_eq!(tda.streamer_requests(), vec!["ADMIN LOGIN", "ACCT_ACTIVITY SUBS", "QUOTE SUBS", "QUOTE UNSUBS", "ADMIN LOGOUT"]);
+    }
+
+    #[tokio::test]
+    async fn test_reconnects_after_disconnect_and_missed_heartbeats() {
+        let tda = MockTda::start();
+        let (hub, user_id) = hub(&tda).await;
+        let mut client = hub.connect(user_id);
+        client.subscribe(&["AAPL".to_string()]);
+        assert_eq!(next(&mut client).await, StreamUpdate::Status { connected: true });
+
+        tda.drop_streams();
+        loop {
+            if next(&mut client).await == (StreamUpdate::Status { connected: false }) {
+                break;
+            }
+        }
+        assert_eq!(next(&mut client).await, StreamUpdate::Status { connected: true });
+        // Subscriptions are restored on the new session.
+        assert!(matches!(next(&mut client).await, StreamUpdate::Quote(_)));
+        assert_eq!(logins(&tda), 2);
+
+        tda.mute_streamer();
+        assert_eq!(next(&mut client).await, StreamUpdate::Status { connected: false });
+        assert_eq!(next(&mut client).await, StreamUpdate::Status { connected: true });
+        assert_eq!(logins(&tda), 3);
+    }
+}
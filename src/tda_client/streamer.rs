@@ -0,0 +1,557 @@
+//! Client side of the TDA streaming API: logs in with the credentials from
+//! [`UserPrincipals`], subscribes to level-one quotes and account activity, and keeps
+//! the connection alive with heartbeat checks and reconnects with exponential backoff.
+
+use super::{
+    error::TdaError,
+    token_manager::TokenManager,
+    user_principals::{TDAmeritradeClientUserPrincipals, UserPrincipals, UserPrincipalsField},
+    TDAmeritradeClient,
+};
+use chrono::DateTime;
+use futures_util::{Sink, SinkExt, StreamExt};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{self, Display, Formatter},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::{
+    sync::{broadcast, watch},
+    time::{sleep, sleep_until, Instant},
+};
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+use uuid::Uuid;
+
+/// Level-one quote fields requested from the `QUOTE` service, by TDA field number.
+const QUOTE_FIELDS: &str = "0,1,2,3,4,5,8,12,13,15,28,29,49";
+/// Subscription key, account, message type and message data.
+const ACCT_ACTIVITY_FIELDS: &str = "0,1,2,3";
+
+#[derive(Clone, Debug)]
+pub struct StreamerConfig {
+    /// Reconnect when TDA sends nothing, not even a heartbeat, for this long.
+    pub heartbeat_timeout: Duration,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for StreamerConfig {
+    fn default() -> Self {
+        Self {
+            heartbeat_timeout: Duration::from_secs(30),
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Doubles the delay after every failed attempt, up to `max`.
+#[derive(Debug)]
+pub struct Backoff {
+    initial: Duration,
+    max: Duration,
+    next: Duration,
+}
+
+impl Backoff {
+    pub fn new(initial: Duration, max: Duration) -> Self {
+        Self { initial, max, next: initial }
+    }
+
+    pub fn next_delay(&mut self) -> Duration {
+        let delay = self.next;
+        self.next = (self.next * 2).min(self.max);
+        delay
+    }
+
+    pub fn reset(&mut self) {
+        self.next = self.initial;
+    }
+}
+
+#[derive(Debug)]
+pub enum StreamerError {
+    Tda(TdaError),
+    WebSocket(Box<tokio_tungstenite::tungstenite::Error>),
+    /// The user principals lack what the streamer needs, e.g. `streamerInfo`.
+    MissingCredentials(&'static str),
+    Login(String),
+    HeartbeatTimeout,
+    Closed,
+}
+
+impl Display for StreamerError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamerError::Tda(e) => write!(f, "{}", e),
+            StreamerError::WebSocket(e) => write!(f, "websocket error: {}", e),
+            StreamerError::MissingCredentials(what) => write!(f, "missing {} in user principals", what),
+            StreamerError::Login(message) => write!(f, "login failed: {}", message),
+            StreamerError::HeartbeatTimeout => write!(f, "no heartbeat from streamer"),
+            StreamerError::Closed => write!(f, "streamer closed the connection"),
+        }
+    }
+}
+
+impl std::error::Error for StreamerError {}
+
+impl From<TdaError> for StreamerError {
+    fn from(e: TdaError) -> Self {
+        StreamerError::Tda(e)
+    }
+}
+
+impl From<tokio_tungstenite::tungstenite::Error> for StreamerError {
+    fn from(e: tokio_tungstenite::tungstenite::Error) -> Self {
+        StreamerError::WebSocket(Box::new(e))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct StreamerRequest {
+    pub service: String,
+    pub command: String,
+    pub requestid: String,
+    pub account: String,
+    pub source: String,
+    pub parameters: BTreeMap<&'static str, String>,
+}
+
+#[derive(Serialize)]
+struct StreamerRequests<'a> {
+    requests: &'a [StreamerRequest],
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct StreamerResponseContent {
+    pub code: i64,
+    pub msg: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct StreamerResponse {
+    pub service: String,
+    pub command: String,
+    #[serde(default)]
+    pub content: StreamerResponseContent,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct StreamerData {
+    pub service: String,
+    #[serde(default)]
+    pub content: Vec<Value>,
+}
+
+/// Any frame the streamer sends. Heartbeats arrive as `notify` entries.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct StreamerMessage {
+    pub response: Vec<StreamerResponse>,
+    pub data: Vec<StreamerData>,
+    pub notify: Vec<Value>,
+}
+
+/// Everything needed to log in to and subscribe on the streamer, taken from user principals
+/// fetched with [`UserPrincipalsField::StreamerConnectionInfo`] and
+/// [`UserPrincipalsField::StreamerSubscriptionKeys`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct StreamerCredentials {
+    pub socket_url: String,
+    pub account_id: String,
+    pub app_id: String,
+    pub token: String,
+    pub subscription_key: String,
+    credential: String,
+}
+
+impl StreamerCredentials {
+    pub fn from_principals(principals: &UserPrincipals) -> Result<Self, StreamerError> {
+        let info = principals.streamer_info.as_ref().ok_or(StreamerError::MissingCredentials("streamerInfo"))?;
+        let subscription_key = principals
+            .streamer_subscription_keys
+            .as_ref()
+            .and_then(|keys| keys.keys.first())
+            .map(|key| key.key.clone())
+            .ok_or(StreamerError::MissingCredentials("streamerSubscriptionKeys"))?;
+        let account = principals
+            .accounts
+            .iter()
+            .find(|account| account.account_id == principals.primary_account_id)
+            .or_else(|| principals.accounts.first())
+            .ok_or(StreamerError::MissingCredentials("accounts"))?;
+        let timestamp = DateTime::parse_from_str(&info.token_timestamp, "%Y-%m-%dT%H:%M:%S%z")
+            .map_err(|_| StreamerError::MissingCredentials("streamerInfo.tokenTimestamp"))?
+            .timestamp_millis();
+        let credential = url::form_urlencoded::Serializer::new(String::new())
+            .append_pair("userid", &account.account_id)
+            .append_pair("token", &info.token)
+            .append_pair("company", &account.company)
+            .append_pair("segment", &account.segment)
+            .append_pair("cddomain", &account.account_cd_domain_id)
+            .append_pair("usergroup", &info.user_group)
+            .append_pair("accesslevel", &info.access_level)
+            .append_pair("authorized", "Y")
+            .append_pair("timestamp", &timestamp.to_string())
+            .append_pair("appid", &info.app_id)
+            .append_pair("acl", &info.acl)
+            .finish();
+        // TDA hands out a bare host; a full URL (as the mock server uses) is taken as is.
+        let socket_url = if info.streamer_socket_url.contains("://") {
+            info.streamer_socket_url.clone()
+        } else {
+            format!("wss://{}/ws", info.streamer_socket_url)
+        };
+        Ok(Self {
+            socket_url,
+            account_id: account.account_id.clone(),
+            app_id: info.app_id.clone(),
+            token: info.token.clone(),
+            subscription_key,
+            credential,
+        })
+    }
+
+    pub fn request(&self, request_id: u64, service: &str, command: &str, parameters: BTreeMap<&'static str, String>) -> StreamerRequest {
+        StreamerRequest {
+            service: service.to_string(),
+            command: command.to_string(),
+            requestid: request_id.to_string(),
+            account: self.account_id.clone(),
+            source: self.app_id.clone(),
+            parameters,
+        }
+    }
+
+    pub fn login(&self, request_id: u64) -> StreamerRequest {
+        let parameters = BTreeMap::from([("credential", self.credential.clone()), ("token", self.token.clone()), ("version", "1.0".to_string())]);
+        self.request(request_id, "ADMIN", "LOGIN", parameters)
+    }
+
+    pub fn subscribe_quotes(&self, request_id: u64, symbols: &BTreeSet<String>) -> StreamerRequest {
+        let keys = symbols.iter().cloned().collect::<Vec<String>>().join(",");
+        let parameters = BTreeMap::from([("keys", keys), ("fields", QUOTE_FIELDS.to_string())]);
+        self.request(request_id, "QUOTE", "SUBS", parameters)
+    }
+
+    pub fn subscribe_account_activity(&self, request_id: u64) -> StreamerRequest {
+        let parameters = BTreeMap::from([("keys", self.subscription_key.clone()), ("fields", ACCT_ACTIVITY_FIELDS.to_string())]);
+        self.request(request_id, "ACCT_ACTIVITY", "SUBS", parameters)
+    }
+}
+
+/// A level-one quote update. TDA only sends the fields that changed, so everything
+/// but the symbol is optional and clients merge updates into what they already have.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LevelOneQuote {
+    pub symbol: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bid_price: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ask_price: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_price: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bid_size: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ask_size: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_volume: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub high_price: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub low_price: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub close_price: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub open_price: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net_change: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mark: Option<f64>,
+}
+
+impl LevelOneQuote {
+    /// Maps one `QUOTE` content entry, keyed by field number, e.g. `{"key":"AAPL","1":142.1}`.
+    pub fn from_content(content: &Value) -> Option<Self> {
+        let float = |field: &str| content.get(field).and_then(Value::as_f64);
+        let integer = |field: &str| content.get(field).and_then(Value::as_i64);
+        Some(Self {
+            symbol: content.get("key")?.as_str()?.to_string(),
+            bid_price: float("1"),
+            ask_price: float("2"),
+            last_price: float("3"),
+            bid_size: integer("4"),
+            ask_size: integer("5"),
+            total_volume: integer("8"),
+            high_price: float("12"),
+            low_price: float("13"),
+            close_price: float("15"),
+            open_price: float("28"),
+            net_change: float("29"),
+            mark: float("49"),
+        })
+    }
+}
+
+/// An order event on one of the user's accounts; `message_data` is TDA's XML payload.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountActivity {
+    pub account_id: String,
+    /// e.g. `OrderEntryRequest`, `OrderFill` or `UROUT` (cancelled).
+    pub message_type: String,
+    pub message_data: String,
+}
+
+impl AccountActivity {
+    pub fn from_content(content: &Value) -> Option<Self> {
+        let text = |field: &str| content.get(field).and_then(Value::as_str).unwrap_or_default().to_string();
+        let message_type = text("2");
+        // The first message after subscribing only confirms the subscription.
+        if message_type.is_empty() || message_type == "SUBSCRIBED" {
+            return None;
+        }
+        Some(Self {
+            account_id: text("1"),
+            message_type,
+            message_data: text("3"),
+        })
+    }
+}
+
+/// What is fanned out to browser clients.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StreamUpdate {
+    Status { connected: bool },
+    Quote(LevelOneQuote),
+    AccountActivity(AccountActivity),
+}
+
+/// Keeps one user's streamer session alive, following the symbols in `symbols`, until
+/// the sending side of `symbols` is dropped because the last browser client left.
+pub async fn run(
+    tda_client: TDAmeritradeClient,
+    token_manager: TokenManager,
+    user_id: Uuid,
+    config: StreamerConfig,
+    mut symbols: watch::Receiver<BTreeSet<String>>,
+    updates: broadcast::Sender<StreamUpdate>,
+    connected: Arc<AtomicBool>,
+) {
+    let mut backoff = Backoff::new(config.initial_backoff, config.max_backoff);
+    loop {
+        let session = Session {
+            tda_client: &tda_client,
+            token_manager: &token_manager,
+            user_id,
+            config: &config,
+            updates: &updates,
+            connected: &connected,
+        };
+        match session.run(&mut symbols, &mut backoff).await {
+            Ok(()) => return,
+            Err(e) => warn!("Streamer for {} disconnected: {}", user_id, e),
+        }
+        if connected.swap(false, Ordering::SeqCst) {
+            updates.send(StreamUpdate::Status { connected: false }).ok();
+        }
+        let delay = sleep(backoff.next_delay());
+        tokio::pin!(delay);
+        loop {
+            tokio::select! {
+                _ = &mut delay => break,
+                changed = symbols.changed() => if changed.is_err() { return },
+            }
+        }
+    }
+}
+
+struct Session<'a> {
+    tda_client: &'a TDAmeritradeClient,
+    token_manager: &'a TokenManager,
+    user_id: Uuid,
+    config: &'a StreamerConfig,
+    updates: &'a broadcast::Sender<StreamUpdate>,
+    connected: &'a AtomicBool,
+}
+
+async fn send<S>(sink: &mut S, request: StreamerRequest) -> Result<(), StreamerError>
+where
+    S: Sink<Message, Error = tokio_tungstenite::tungstenite::Error> + Unpin,
+{
+    let text = serde_json::to_string(&StreamerRequests { requests: &[request] }).unwrap_or_default();
+    sink.send(Message::Text(text)).await?;
+    Ok(())
+}
+
+impl Session<'_> {
+    /// Returns `Ok` only when told to stop; every failure is an `Err` to reconnect after.
+    async fn run(&self, symbols: &mut watch::Receiver<BTreeSet<String>>, backoff: &mut Backoff) -> Result<(), StreamerError> {
+        let tda_client = self.tda_client;
+        let fields = [UserPrincipalsField::StreamerConnectionInfo, UserPrincipalsField::StreamerSubscriptionKeys];
+        let principals = self
+            .token_manager
+            .with_access_token(self.user_id, |token| async move { tda_client.get_user_principals(&token, &fields).await })
+            .await?;
+        let credentials = StreamerCredentials::from_principals(&principals)?;
+        let (socket, _) = connect_async(credentials.socket_url.as_str()).await?;
+        let (mut sink, mut stream) = socket.split();
+        let mut request_id = 0;
+        let mut deadline = Instant::now() + self.config.heartbeat_timeout;
+
+        send(&mut sink, credentials.login(request_id)).await?;
+        loop {
+            let message = tokio::select! {
+                message = stream.next() => message,
+                _ = sleep_until(deadline) => return Err(StreamerError::HeartbeatTimeout),
+            };
+            let text = match message {
+                Some(Ok(Message::Text(text))) => text,
+                Some(Ok(Message::Close(_))) | None => return Err(StreamerError::Closed),
+                Some(Err(e)) => return Err(e.into()),
+                Some(Ok(_)) => continue,
+            };
+            let message = serde_json::from_str::<StreamerMessage>(&text).unwrap_or_default();
+            if let Some(login) = message.response.iter().find(|response| response.service == "ADMIN" && response.command == "LOGIN") {
+                if login.content.code != 0 {
+                    return Err(StreamerError::Login(login.content.msg.clone()));
+                }
+                break;
+            }
+        }
+        info!("Streamer for {} logged in", self.user_id);
+        backoff.reset();
+        self.connected.store(true, Ordering::SeqCst);
+        self.updates.send(StreamUpdate::Status { connected: true }).ok();
+
+        request_id += 1;
+        send(&mut sink, credentials.subscribe_account_activity(request_id)).await?;
+        let mut subscribed = BTreeSet::new();
+        loop {
+            let wanted = symbols.borrow_and_update().clone();
+            if wanted != subscribed {
+                request_id += 1;
+                let request = if wanted.is_empty() {
+                    let parameters = BTreeMap::from([("keys", subscribed.iter().cloned().collect::<Vec<String>>().join(","))]);
+                    credentials.request(request_id, "QUOTE", "UNSUBS", parameters)
+                } else {
+                    // SUBS replaces the previous subscription, so it always lists every symbol.
+                    credentials.subscribe_quotes(request_id, &wanted)
+                };
+                send(&mut sink, request).await?;
+                subscribed = wanted;
+            }
+
+            tokio::select! {
+                message = stream.next() => {
+                    deadline = Instant::now() + self.config.heartbeat_timeout;
+                    match message {
+                        Some(Ok(Message::Text(text))) => self.dispatch(&text),
+                        Some(Ok(Message::Close(_))) | None => return Err(StreamerError::Closed),
+                        Some(Err(e)) => return Err(e.into()),
+                        Some(Ok(_)) => {}
+                    }
+                }
+                changed = symbols.changed() => {
+                    if changed.is_err() {
+                        request_id += 1;
+                        send(&mut sink, credentials.request(request_id, "ADMIN", "LOGOUT", BTreeMap::new())).await.ok();
+                        sink.close().await.ok();
+                        return Ok(());
+                    }
+                }
+                _ = sleep_until(deadline) => return Err(StreamerError::HeartbeatTimeout),
+            }
+        }
+    }
+
+    fn dispatch(&self, text: &str) {
+        let message = match serde_json::from_str::<StreamerMessage>(text) {
+            Ok(message) => message,
+            Err(e) => return warn!("Unexpected streamer message {}: {}", text, e),
+        };
+        for response in message.response.iter().filter(|response| response.content.code != 0) {
+            warn!("Streamer rejected {} {}: {}", response.service, response.command, response.content.msg);
+        }
+        for data in &message.data {
+            let updates: Vec<StreamUpdate> = match data.service.as_str() {
+                "QUOTE" => data.content.iter().filter_map(LevelOneQuote::from_content).map(StreamUpdate::Quote).collect(),
+                "ACCT_ACTIVITY" => data.content.iter().filter_map(AccountActivity::from_content).map(StreamUpdate::AccountActivity).collect(),
+                _ => vec![],
+            };
+            for update in updates {
+                self.updates.send(update).ok();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AccountActivity, Backoff, LevelOneQuote, StreamUpdate, StreamerCredentials};
+    use crate::tda_client::user_principals::UserPrincipals;
+    use serde_json::json;
+    use std::{collections::BTreeSet, time::Duration};
+
+    const USER_PRINCIPALS: &str = include_str!("fixtures/user_principals.json");
+
+    #[test]
+    fn test_backoff_doubles_up_to_max() {
+        let mut backoff = Backoff::new(Duration::from_secs(1), Duration::from_secs(5));
+        let delays: Vec<u64> = (0..5).map(|_| backoff.next_delay().as_secs()).collect();
+        assert_eq!(delays, vec![1, 2, 4, 5, 5]);
+        backoff.reset();
+        assert_eq!(backoff.next_delay(), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_credentials_from_principals() {
+        let principals = serde_json::from_str::<UserPrincipals>(USER_PRINCIPALS).unwrap();
+        let credentials = StreamerCredentials::from_principals(&principals).unwrap();
+        assert_eq!(credentials.socket_url, "wss://streamer-ws.tdameritrade.com/ws");
+        assert_eq!(credentials.subscription_key, "mock-subscription-key");
+
+        let login = serde_json::to_value(credentials.login(0)).unwrap();
+        assert_eq!(login["service"], "ADMIN");
+        assert_eq!(login["account"], "123456789");
+        assert_eq!(login["source"], "TDATRADERX");
+        let credential = login["parameters"]["credential"].as_str().unwrap();
+        assert!(credential.starts_with("userid=123456789&token=mock-streamer-token&company=AMER"));
+        assert!(credential.contains("&timestamp=1677679332000&appid=TDATRADERX&"));
+
+        let symbols = BTreeSet::from(["MSFT".to_string(), "AAPL".to_string()]);
+        let subscribe = serde_json::to_value(credentials.subscribe_quotes(1, &symbols)).unwrap();
+        assert_eq!(subscribe["parameters"]["keys"], "AAPL,MSFT");
+
+        let mut principals = principals;
+        principals.streamer_info = None;
+        assert!(StreamerCredentials::from_principals(&principals).is_err());
+    }
+
+    #[test]
+    fn test_parse_stream_content() {
+        let quote = LevelOneQuote::from_content(&json!({"key": "AAPL", "1": 142.1, "2": 142.12, "8": 51234567, "49": 142.11})).unwrap();
+        assert_eq!(quote.bid_price, Some(142.1));
+        assert_eq!(quote.total_volume, Some(51234567));
+        assert_eq!(quote.last_price, None);
+        assert_eq!(
+            serde_json::to_value(StreamUpdate::Quote(quote)).unwrap(),
+            json!({"type": "quote", "symbol": "AAPL", "bidPrice": 142.1, "askPrice": 142.12, "totalVolume": 51234567, "mark": 142.11})
+        );
+
+        assert_eq!(AccountActivity::from_content(&json!({"1": "123456789", "2": "SUBSCRIBED", "3": ""})), None);
+        let activity = AccountActivity::from_content(&json!({"1": "123456789", "2": "OrderFill", "3": "<OrderFillMessage/>"})).unwrap();
+        assert_eq!(activity.message_type, "OrderFill");
+    }
+}
@@ -0,0 +1,284 @@
+use super::{
+    auth::{TDAmeritradeClientAuth, TokenResponse},
+    error::TdaError,
+    TDAmeritradeClient,
+};
+use crate::database_client::{models::BrokerConnection, repository::BrokerConnectionRepository, UpsertBrokerConnection, BROKER_TDA};
+use chrono::{Duration, NaiveDateTime, Utc};
+use log::error;
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{Arc, Mutex},
+};
+use tokio::sync::Mutex as AsyncMutex;
+use uuid::Uuid;
+
+const ACCESS_TOKEN_EXPIRES_IN: u64 = 1800; // 30 minutes
+const REFRESH_TOKEN_EXPIRES_IN: u64 = 7776000; // 90 days
+const REFRESH_MARGIN: i64 = 60; // refresh a minute before TDA would reject the token
+
+#[derive(Clone, Debug)]
+pub struct AccessToken {
+    pub access_token: String,
+    pub expires_at: NaiveDateTime,
+}
+
+impl AccessToken {
+    fn is_fresh(&self) -> bool {
+        self.expires_at - Duration::seconds(REFRESH_MARGIN) > Utc::now().naive_utc()
+    }
+}
+
+type TokenSlot = Arc<AsyncMutex<Option<AccessToken>>>;
+
+/// Hands out valid TDA access tokens per user, refreshing them through
+/// [`TDAmeritradeClientAuth::exchange_refresh_token_for_token`] shortly before they
+/// expire. Each user has their own lock so concurrent requests share one refresh.
+#[derive(Clone)]
+pub struct TokenManager {
+    tda_client: TDAmeritradeClient,
+    broker_connections: Arc<dyn BrokerConnectionRepository>,
+    slots: Arc<Mutex<HashMap<Uuid, TokenSlot>>>,
+}
+
+impl TokenManager {
+    pub fn new(tda_client: TDAmeritradeClient, broker_connections: Arc<dyn BrokerConnectionRepository>) -> Self {
+        Self {
+            tda_client,
+            broker_connections,
+            slots: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    fn slot(&self, user_id: Uuid) -> TokenSlot {
+        let mut slots = self.slots.lock().unwrap();
+        if let Some(slot) = slots.get(&user_id) {
+            return slot.clone();
+        }
+        // A slot no request holds only caches a token that is also in the database.
+        slots.retain(|_, slot| Arc::strong_count(slot) > 1);
+        slots.entry(user_id).or_default().clone()
+    }
+
+    async fn connection(&self, user_id: Uuid) -> Result<BrokerConnection, TdaError> {
+        match self.broker_connections.get_broker_connection(user_id, BROKER_TDA).await {
+            Ok(Some(connection)) => Ok(connection),
+            Ok(None) => Err(TdaError::Unauthorized("no TD Ameritrade connection".to_string())),
+            Err(e) => Err(TdaError::Storage(e)),
+        }
+    }
+
+    pub async fn access_token(&self, user_id: Uuid) -> Result<String, TdaError> {
+        let slot = self.slot(user_id);
+        let mut cached = slot.lock().await;
+        if let Some(token) = cached.as_ref().filter(|token| token.is_fresh()) {
+            return Ok(token.access_token.clone());
+        }
+        let connection = self.connection(user_id).await?;
+        let stored = AccessToken {
+            access_token: connection.access_token.clone(),
+            expires_at: connection.access_token_expires_at,
+        };
+        let token = if stored.is_fresh() { stored } else { self.refresh_connection(user_id, &connection).await? };
+        *cached = Some(token.clone());
+        Ok(token.access_token)
+    }
+
+    /// Forces a refresh unless another request already replaced `stale_access_token`.
+    pub async fn refresh(&self, user_id: Uuid, stale_access_token: Option<&str>) -> Result<AccessToken, TdaError> {
+        let slot = self.slot(user_id);
+        let mut cached = slot.lock().await;
+        if let (Some(token), Some(stale_access_token)) = (cached.as_ref(), stale_access_token) {
+            if token.access_token != stale_access_token && token.is_fresh() {
+                return Ok(token.clone());
+            }
+        }
+        let connection = self.connection(user_id).await?;
+        let token = self.refresh_connection(user_id, &connection).await?;
+        *cached = Some(token.clone());
+        Ok(token)
+    }
+
+    /// Runs `f` with a valid access token, refreshing and retrying once if TDA answers 401.
+    pub async fn with_access_token<T, F, Fut>(&self, user_id: Uuid, f: F) -> Result<T, TdaError>
+    where
+        F: Fn(String) -> Fut,
+        Fut: Future<Output = Result<T, TdaError>>,
+    {
+        let access_token = self.access_token(user_id).await?;
+        match f(access_token.clone()).await {
+            Err(TdaError::Unauthorized(_)) => {
+                let token = self.refresh(user_id, Some(&access_token)).await?;
+                f(token.access_token).await
+            }
+            result => result,
+        }
+    }
+
+    /// Persists a token response from TDA, e.g. after the OAuth callback.
+    pub async fn store(&self, user_id: Uuid, token_response: &TokenResponse) -> Result<(), TdaError> {
+        let slot = self.slot(user_id);
+        let mut cached = slot.lock().await;
+        *cached = Some(self.save(user_id, token_response, None).await?);
+        Ok(())
+    }
+
+    async fn refresh_connection(&self, user_id: Uuid, connection: &BrokerConnection) -> Result<AccessToken, TdaError> {
+        if connection.refresh_token_expires_at <= Utc::now().naive_utc() {
+            return Err(TdaError::Unauthorized("TD Ameritrade connection expired".to_string()));
+        }
+        let token_response = match self.tda_client.exchange_refresh_token_for_token(&connection.refresh_token).await {
+            Ok(token_response) => token_response,
+            Err(TdaError::Rejected { message, .. }) => return Err(TdaError::Unauthorized(message)),
+            Err(e) => return Err(e),
+        };
+        self.save(user_id, &token_response, Some(connection)).await
+    }
+
+    /// TDA omits the refresh token when refreshing an access token, in which case
+    /// the `previous` connection's refresh token is kept. A response that leaves us
+    /// without either token is rejected rather than saved as a connection that can never work.
+    async fn save(&self, user_id: Uuid, token_response: &TokenResponse, previous: Option<&BrokerConnection>) -> Result<AccessToken, TdaError> {
+        let missing = |token: &str| TdaError::Decode {
+            message: format!("token response without {}", token),
+            body: String::new(),
+        };
+        let now = Utc::now().naive_utc();
+        let access_token = token_response.access_token.clone().filter(|token| !token.is_empty()).ok_or_else(|| missing("access_token"))?;
+        let access_token_expires_at = now + Duration::seconds(token_response.expires_in.unwrap_or(ACCESS_TOKEN_EXPIRES_IN) as i64);
+        let (refresh_token, refresh_token_expires_at) = match (&token_response.refresh_token, previous) {
+            (Some(refresh_token), _) => {
+                let refresh_token_expires_in = token_response.refresh_token_expires_in.unwrap_or(REFRESH_TOKEN_EXPIRES_IN);
+                (refresh_token.clone(), now + Duration::seconds(refresh_token_expires_in as i64))
+            }
+            (None, Some(previous)) => (previous.refresh_token.clone(), previous.refresh_token_expires_at),
+            (None, None) => return Err(missing("refresh_token")),
+        };
+        let upsert = UpsertBrokerConnection {
+            user_id,
+            broker: BROKER_TDA.to_string(),
+            access_token: access_token.clone(),
+            refresh_token,
+            access_token_expires_at,
+            refresh_token_expires_at,
+        };
+        if let Err(e) = self.broker_connections.upsert_broker_connection(upsert).await {
+            // The new token is still usable for this process; it just won't survive a restart.
+            error!("Error saving TDA tokens for {}: {}", user_id, e);
+        }
+        Ok(AccessToken {
+            access_token,
+            expires_at: access_token_expires_at,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AccessToken, TokenManager};
+    use crate::{
+        database_client::memory::InMemoryDatabase,
+        tda_client::{
+            accounts::TDAmeritradeClientAccounts,
+            auth::TokenResponse,
+            error::TdaError,
+            mock::{token_response, MockTda, ACCESS_TOKEN},
+        },
+    };
+    use chrono::{Duration, Utc};
+    use std::{collections::HashSet, sync::Arc};
+    use uuid::Uuid;
+
+    async fn token_manager(tda: &MockTda, expires_in: u64) -> (TokenManager, Uuid) {
+        let token_manager = TokenManager::new(tda.client(), Arc::new(InMemoryDatabase::default()));
+        let user_id = Uuid::new_v4();
+        let token_response = TokenResponse {
+            expires_in: Some(expires_in),
+            ..token_response()
+        };
+        token_manager.store(user_id, &token_response).await.unwrap();
+        (token_manager, user_id)
+    }
+
+    #[test]
+    fn test_access_token_refreshes_before_expiry() {
+        let now = Utc::now().naive_utc();
+        let token = |expires_in: i64| AccessToken {
+            access_token: "token".to_string(),
+            expires_at: now + Duration::seconds(expires_in),
+        };
+        assert!(token(1800).is_fresh());
+        assert!(!token(30).is_fresh());
+        assert!(!token(-1).is_fresh());
+    }
+
+    #[tokio::test]
+    async fn test_refreshes_token_close_to_expiry() {
+        let tda = MockTda::start();
+        let (token_manager, user_id) = token_manager(&tda, 30).await;
+        let access_token = token_manager.access_token(user_id).await.unwrap();
+        assert_eq!(access_token, format!("{}-1", ACCESS_TOKEN));
+        assert_eq!(token_manager.access_token(user_id).await.unwrap(), access_token);
+        assert_eq!(tda.requests(), vec!["POST /v1/oauth2/token"]);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_retries_share_one_refresh() {
+        let tda = MockTda::start();
+        let (token_manager, user_id) = token_manager(&tda, 1800).await;
+        let client = tda.client();
+        tda.expire_access_tokens();
+        let requests = (0..5).map(|_| {
+            let (token_manager, client) = (token_manager.clone(), client.clone());
+            tokio::spawn(async move {
+                token_manager
+                    .with_access_token(user_id, |token| {
+                        let client = client.clone();
+                        async move { client.get_accounts(&token).await }
+                    })
+                    .await
+            })
+        });
+        for request in requests.collect::<Vec<_>>() {
+            assert_eq!(request.await.unwrap().unwrap().len(), 2);
+        }
+        let refreshes = tda.requests().iter().filter(|request| request.ends_with("/oauth2/token")).count();
+        assert_eq!(refreshes, 1);
+    }
+
+    #[tokio::test]
+    async fn test_store_rejects_response_without_tokens() {
+        let tda = MockTda::start();
+        let token_manager = TokenManager::new(tda.client(), Arc::new(InMemoryDatabase::default()));
+        let user_id = Uuid::new_v4();
+        let without_refresh_token = TokenResponse {
+            refresh_token: None,
+            ..token_response()
+        };
+        assert!(matches!(token_manager.store(user_id, &without_refresh_token).await, Err(TdaError::Decode { .. })));
+        assert!(matches!(token_manager.store(user_id, &TokenResponse::default()).await, Err(TdaError::Decode { .. })));
+        assert!(matches!(token_manager.access_token(user_id).await, Err(TdaError::Unauthorized(_))));
+    }
+
+    #[tokio::test]
+    async fn test_idle_slots_are_evicted() {
+        let tda = MockTda::start();
+        let (token_manager, user_id) = token_manager(&tda, 1800).await;
+        let (held_user_id, idle_user_id) = (Uuid::new_v4(), Uuid::new_v4());
+        let _held = token_manager.slot(held_user_id);
+        token_manager.access_token(user_id).await.unwrap();
+        token_manager.slot(idle_user_id);
+        let user_ids: HashSet<Uuid> = token_manager.slots.lock().unwrap().keys().copied().collect();
+        assert_eq!(user_ids, HashSet::from([held_user_id, idle_user_id]));
+        // The evicted user's token is loaded from the database again.
+        assert_eq!(token_manager.access_token(user_id).await.unwrap(), ACCESS_TOKEN);
+    }
+
+    #[tokio::test]
+    async fn test_missing_connection_is_unauthorized() {
+        let tda = MockTda::start();
+        let (token_manager, _) = token_manager(&tda, 1800).await;
+        assert!(matches!(token_manager.access_token(Uuid::new_v4()).await, Err(TdaError::Unauthorized(_))));
+    }
+}
@@ -0,0 +1,194 @@
+use super::{
+    accounts::{AssetType, Instruction, PutCall},
+    error::TdaError,
+    TDAmeritradeClient,
+};
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TransactionType {
+    Trade,
+    ReceiveAndDeliver,
+    DividendOrInterest,
+    AchReceipt,
+    AchDisbursement,
+    CashReceipt,
+    CashDisbursement,
+    ElectronicFund,
+    WireOut,
+    WireIn,
+    Journal,
+    Memorandum,
+    MarginCall,
+    MoneyMarket,
+    SmaAdjustment,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TransactionQueryType {
+    All,
+    Trade,
+    BuyOnly,
+    SellOnly,
+    CashInOrCashOut,
+    Checking,
+    Dividend,
+    Interest,
+    Other,
+    AdvisorFees,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetTransactionsQuery {
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub transaction_type: Option<TransactionQueryType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_date: Option<NaiveDate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_date: Option<NaiveDate>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Fees {
+    pub r_fee: f64,
+    pub additional_fee: f64,
+    pub cdsc_fee: f64,
+    pub reg_fee: f64,
+    pub other_charges: f64,
+    pub commission: f64,
+    pub opt_reg_fee: f64,
+    pub sec_fee: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionInstrument {
+    pub symbol: Option<String>,
+    pub underlying_symbol: Option<String>,
+    pub option_expiration_date: Option<String>,
+    pub option_strike_price: Option<f64>,
+    pub put_call: Option<PutCall>,
+    pub cusip: Option<String>,
+    pub description: Option<String>,
+    pub asset_type: Option<AssetType>,
+    pub bond_maturity_date: Option<String>,
+    pub bond_interest_rate: Option<f64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionItem {
+    pub account_id: Option<i64>,
+    pub amount: Option<f64>,
+    pub price: Option<f64>,
+    #[serde(default)]
+    pub cost: f64,
+    pub parent_order_key: Option<i64>,
+    pub parent_child_indicator: Option<String>,
+    pub instruction: Option<Instruction>,
+    pub position_effect: Option<String>,
+    pub instrument: Option<TransactionInstrument>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Transaction {
+    #[serde(rename = "type")]
+    pub transaction_type: TransactionType,
+    pub clearing_reference_number: Option<String>,
+    pub sub_account: Option<String>,
+    pub settlement_date: Option<String>,
+    pub order_id: Option<String>,
+    pub sma: Option<f64>,
+    pub requirement_reallocation_amount: Option<f64>,
+    pub day_trade_buying_power_effect: Option<f64>,
+    pub net_amount: f64,
+    pub transaction_date: String,
+    pub order_date: Option<String>,
+    pub transaction_sub_type: Option<String>,
+    pub transaction_id: i64,
+    pub cash_balance_effect_flag: Option<bool>,
+    pub description: String,
+    pub ach_status: Option<String>,
+    pub accrued_interest: Option<f64>,
+    #[serde(default)]
+    pub fees: Fees,
+    pub transaction_item: TransactionItem,
+}
+
+#[async_trait]
+pub trait TDAmeritradeClientTransactions {
+    async fn get_transactions(&self, token: &str, account_id: &str, query: &GetTransactionsQuery) -> Result<Vec<Transaction>, TdaError>;
+    async fn get_transaction(&self, token: &str, account_id: &str, transaction_id: &str) -> Result<Transaction, TdaError>;
+}
+
+#[async_trait]
+impl TDAmeritradeClientTransactions for TDAmeritradeClient {
+    async fn get_transactions(&self, token: &str, account_id: &str, query: &GetTransactionsQuery) -> Result<Vec<Transaction>, TdaError> {
+        let url = format!("{}/accounts/{}/transactions", self.base_url, account_id);
+        self.send_json::<Vec<Transaction>>(self.client.get(&url).query(query).bearer_auth(token)).await
+    }
+
+    async fn get_transaction(&self, token: &str, account_id: &str, transaction_id: &str) -> Result<Transaction, TdaError> {
+        let url = format!("{}/accounts/{}/transactions/{}", self.base_url, account_id, transaction_id);
+        self.send_json::<Transaction>(self.client.get(&url).bearer_auth(token)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GetTransactionsQuery, Transaction, TransactionQueryType, TransactionType};
+    use crate::tda_client::accounts::Instruction;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_deserialize_trade_transaction() {
+        let json = r#"{
+            "type": "TRADE",
+            "subAccount": "1",
+            "settlementDate": "2023-03-03",
+            "orderId": "T1234567890",
+            "netAmount": -1425.0,
+            "transactionDate": "2023-03-01T15:30:12+0000",
+            "orderDate": "2023-03-01T15:30:11+0000",
+            "transactionSubType": "BY",
+            "transactionId": 48123456789,
+            "cashBalanceEffectFlag": true,
+            "description": "BUY TRADE",
+            "fees": { "rFee": 0.0, "additionalFee": 0.0, "cdscFee": 0.0, "regFee": 0.0, "otherCharges": 0.0, "commission": 0.0, "optRegFee": 0.0, "secFee": 0.0 },
+            "transactionItem": {
+                "accountId": 123456789,
+                "amount": 10.0,
+                "price": 142.5,
+                "cost": -1425.0,
+                "instruction": "BUY",
+                "positionEffect": "OPENING",
+                "instrument": { "symbol": "AAPL", "cusip": "037833100", "assetType": "EQUITY" }
+            }
+        }"#;
+        let transaction = serde_json::from_str::<Transaction>(json).unwrap();
+        assert_eq!(transaction.transaction_type, TransactionType::Trade);
+        assert_eq!(transaction.transaction_item.instruction, Some(Instruction::Buy));
+        assert_eq!(transaction.transaction_item.instrument.unwrap().symbol.as_deref(), Some("AAPL"));
+    }
+
+    #[test]
+    fn test_serialize_transactions_query() {
+        let query = GetTransactionsQuery {
+            transaction_type: Some(TransactionQueryType::Trade),
+            symbol: Some("AAPL".to_string()),
+            start_date: NaiveDate::from_ymd_opt(2023, 1, 1),
+            end_date: None,
+        };
+        let request = reqwest::Client::new().get("http://localhost/transactions").query(&query).build().unwrap();
+        assert_eq!(request.url().query(), Some("type=TRADE&symbol=AAPL&startDate=2023-01-01"));
+    }
+}
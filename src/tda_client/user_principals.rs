@@ -0,0 +1,168 @@
+use super::{error::TdaError, TDAmeritradeClient};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Optional parts of the user principals response.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum UserPrincipalsField {
+    StreamerSubscriptionKeys,
+    StreamerConnectionInfo,
+    Preferences,
+    SurrogateIds,
+}
+
+impl UserPrincipalsField {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UserPrincipalsField::StreamerSubscriptionKeys => "streamerSubscriptionKeys",
+            UserPrincipalsField::StreamerConnectionInfo => "streamerConnectionInfo",
+            UserPrincipalsField::Preferences => "preferences",
+            UserPrincipalsField::SurrogateIds => "surrogateIds",
+        }
+    }
+}
+
+/// What the streaming API needs to log in, from [`UserPrincipalsField::StreamerConnectionInfo`].
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct StreamerInfo {
+    pub streamer_binary_url: String,
+    pub streamer_socket_url: String,
+    pub token: String,
+    pub token_timestamp: String,
+    pub user_group: String,
+    pub access_level: String,
+    pub acl: String,
+    pub app_id: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct StreamerSubscriptionKey {
+    pub key: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct StreamerSubscriptionKeys {
+    pub keys: Vec<StreamerSubscriptionKey>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Preferences {
+    pub express_trading: bool,
+    pub direct_options_routing: bool,
+    pub direct_equity_routing: bool,
+    pub default_equity_order_leg_instruction: String,
+    pub default_equity_order_type: String,
+    pub default_equity_order_price_link_type: String,
+    pub default_equity_order_duration: String,
+    pub default_equity_order_market_session: String,
+    pub default_equity_quantity: i64,
+    pub mutual_fund_tax_lot_method: String,
+    pub option_tax_lot_method: String,
+    pub equity_tax_lot_method: String,
+    pub default_advanced_tool_launch: String,
+    pub auth_token_timeout: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct UserPrincipalsAccount {
+    pub account_id: String,
+    /// The nickname the user gave the account in TDA, e.g. `Roth IRA`.
+    pub display_name: String,
+    pub account_cd_domain_id: String,
+    pub company: String,
+    pub segment: String,
+    pub acl: String,
+    pub preferences: Option<Preferences>,
+    pub surrogate_ids: Option<HashMap<String, String>>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct UserPrincipals {
+    pub user_id: String,
+    pub user_cd_domain_id: String,
+    pub primary_account_id: String,
+    pub last_login_time: String,
+    pub token_expiration_time: String,
+    pub login_time: String,
+    pub access_level: String,
+    pub stale_password: bool,
+    pub professional_status: String,
+    pub streamer_info: Option<StreamerInfo>,
+    pub streamer_subscription_keys: Option<StreamerSubscriptionKeys>,
+    pub exchange_agreements: HashMap<String, String>,
+    pub accounts: Vec<UserPrincipalsAccount>,
+}
+
+impl UserPrincipals {
+    /// Account id to display name, for labelling the accounts returned by `get_accounts`.
+    pub fn account_display_names(&self) -> HashMap<String, String> {
+        self.accounts.iter().map(|account| (account.account_id.clone(), account.display_name.clone())).collect()
+    }
+}
+
+#[async_trait]
+pub trait TDAmeritradeClientUserPrincipals {
+    async fn get_user_principals(&self, token: &str, fields: &[UserPrincipalsField]) -> Result<UserPrincipals, TdaError>;
+}
+
+#[async_trait]
+impl TDAmeritradeClientUserPrincipals for TDAmeritradeClient {
+    async fn get_user_principals(&self, token: &str, fields: &[UserPrincipalsField]) -> Result<UserPrincipals, TdaError> {
+        let url = format!("{}/userprincipals", self.base_url);
+        let mut request = self.client.get(&url).bearer_auth(token);
+        if !fields.is_empty() {
+            let fields: Vec<&str> = fields.iter().map(UserPrincipalsField::as_str).collect();
+            request = request.query(&[("fields", fields.join(","))]);
+        }
+        self.send_json::<UserPrincipals>(request).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TDAmeritradeClientUserPrincipals, UserPrincipals, UserPrincipalsField};
+    use crate::tda_client::{
+        accounts::GetAccountsResponse,
+        mock::{MockTda, ACCESS_TOKEN, ACCOUNT_ID},
+    };
+
+    const ACCOUNTS: &str = include_str!("fixtures/accounts.json");
+    const USER_PRINCIPALS: &str = include_str!("fixtures/user_principals.json");
+
+    #[test]
+    fn test_join_accounts_with_display_names() {
+        let principals = serde_json::from_str::<UserPrincipals>(USER_PRINCIPALS).unwrap();
+        assert_eq!(principals.streamer_info.as_ref().unwrap().app_id, "TDATRADERX");
+        assert_eq!(principals.streamer_subscription_keys.as_ref().unwrap().keys.len(), 1);
+        assert_eq!(principals.accounts[0].preferences.as_ref().unwrap().default_equity_order_type, "LIMIT");
+
+        let display_names = principals.account_display_names();
+        let accounts = serde_json::from_str::<Vec<GetAccountsResponse>>(ACCOUNTS).unwrap();
+        let labels: Vec<&str> = accounts.iter().map(|account| display_names[account.securities_account.account_id()].as_str()).collect();
+        assert_eq!(labels, vec!["Trading", "Roth IRA"]);
+    }
+
+    #[tokio::test]
+    async fn test_user_principals_fields() {
+        let tda = MockTda::start();
+        let client = tda.client();
+        let principals = client.get_user_principals(ACCESS_TOKEN, &[]).await.unwrap();
+        assert!(principals.streamer_info.is_none());
+        assert!(principals.accounts[0].preferences.is_none());
+        assert_eq!(principals.account_display_names()[ACCOUNT_ID], "Trading");
+        let fields = [UserPrincipalsField::StreamerConnectionInfo, UserPrincipalsField::SurrogateIds];
+        let principals = client.get_user_principals(ACCESS_TOKEN, &fields).await.unwrap();
+        assert!(principals.streamer_info.is_some());
+        assert!(principals.streamer_subscription_keys.is_none());
+        assert!(principals.accounts[0].surrogate_ids.is_some());
+        assert_eq!(tda.requests().last().unwrap(), "GET /v1/userprincipals");
+    }
+}
@@ -0,0 +1,205 @@
+use super::{accounts::AssetType, error::TdaError, id_from_location, TDAmeritradeClient};
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchlistInstrument {
+    pub symbol: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub asset_type: AssetType,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchlistItem {
+    /// Assigned by TDA; identifies the item when updating a watchlist.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sequence_id: Option<i64>,
+    #[serde(default)]
+    pub quantity: f64,
+    #[serde(default)]
+    pub average_price: f64,
+    #[serde(default)]
+    pub commission: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub purchased_date: Option<NaiveDate>,
+    pub instrument: WatchlistInstrument,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+}
+
+impl WatchlistItem {
+    pub fn new(symbol: &str, asset_type: AssetType) -> Self {
+        Self {
+            sequence_id: None,
+            quantity: 0.0,
+            average_price: 0.0,
+            commission: 0.0,
+            purchased_date: None,
+            instrument: WatchlistInstrument {
+                symbol: symbol.to_string(),
+                description: None,
+                asset_type,
+            },
+            status: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Watchlist {
+    pub name: String,
+    pub watchlist_id: String,
+    pub account_id: String,
+    #[serde(default)]
+    pub status: Option<String>,
+    #[serde(default)]
+    pub watchlist_items: Vec<WatchlistItem>,
+}
+
+/// Body for creating, replacing and updating a watchlist. Updates append items without a
+/// `sequence_id` and change the ones with one; replacing drops every item not listed.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchlistRequest {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub watchlist_id: Option<String>,
+    #[serde(default)]
+    pub watchlist_items: Vec<WatchlistItem>,
+}
+
+#[async_trait]
+pub trait TDAmeritradeClientWatchlists {
+    async fn get_watchlists_for_all_accounts(&self, token: &str) -> Result<Vec<Watchlist>, TdaError>;
+    async fn get_watchlists(&self, token: &str, account_id: &str) -> Result<Vec<Watchlist>, TdaError>;
+    async fn get_watchlist(&self, token: &str, account_id: &str, watchlist_id: &str) -> Result<Watchlist, TdaError>;
+    async fn create_watchlist(&self, token: &str, account_id: &str, watchlist: &WatchlistRequest) -> Result<Option<String>, TdaError>;
+    async fn replace_watchlist(&self, token: &str, account_id: &str, watchlist_id: &str, watchlist: &WatchlistRequest) -> Result<(), TdaError>;
+    async fn update_watchlist(&self, token: &str, account_id: &str, watchlist_id: &str, watchlist: &WatchlistRequest) -> Result<(), TdaError>;
+    async fn delete_watchlist(&self, token: &str, account_id: &str, watchlist_id: &str) -> Result<(), TdaError>;
+}
+
+#[async_trait]
+impl TDAmeritradeClientWatchlists for TDAmeritradeClient {
+    async fn get_watchlists_for_all_accounts(&self, token: &str) -> Result<Vec<Watchlist>, TdaError> {
+        let url = format!("{}/accounts/watchlists", self.base_url);
+        self.send_json::<Vec<Watchlist>>(self.client.get(&url).bearer_auth(token)).await
+    }
+
+    async fn get_watchlists(&self, token: &str, account_id: &str) -> Result<Vec<Watchlist>, TdaError> {
+        let url = format!("{}/accounts/{}/watchlists", self.base_url, account_id);
+        self.send_json::<Vec<Watchlist>>(self.client.get(&url).bearer_auth(token)).await
+    }
+
+    async fn get_watchlist(&self, token: &str, account_id: &str, watchlist_id: &str) -> Result<Watchlist, TdaError> {
+        let url = format!("{}/accounts/{}/watchlists/{}", self.base_url, account_id, watchlist_id);
+        self.send_json::<Watchlist>(self.client.get(&url).bearer_auth(token)).await
+    }
+
+    async fn create_watchlist(&self, token: &str, account_id: &str, watchlist: &WatchlistRequest) -> Result<Option<String>, TdaError> {
+        let url = format!("{}/accounts/{}/watchlists", self.base_url, account_id);
+        let response = self.send(self.client.post(&url).bearer_auth(token).json(watchlist)).await?;
+        Ok(id_from_location(&response, "watchlists"))
+    }
+
+    async fn replace_watchlist(&self, token: &str, account_id: &str, watchlist_id: &str, watchlist: &WatchlistRequest) -> Result<(), TdaError> {
+        let url = format!("{}/accounts/{}/watchlists/{}", self.base_url, account_id, watchlist_id);
+        self.send(self.client.put(&url).bearer_auth(token).json(watchlist)).await?;
+        Ok(())
+    }
+
+    async fn update_watchlist(&self, token: &str, account_id: &str, watchlist_id: &str, watchlist: &WatchlistRequest) -> Result<(), TdaError> {
+        let url = format!("{}/accounts/{}/watchlists/{}", self.base_url, account_id, watchlist_id);
+        self.send(self.client.patch(&url).bearer_auth(token).json(watchlist)).await?;
+        Ok(())
+    }
+
+    async fn delete_watchlist(&self, token: &str, account_id: &str, watchlist_id: &str) -> Result<(), TdaError> {
+        let url = format!("{}/accounts/{}/watchlists/{}", self.base_url, account_id, watchlist_id);
+        self.send(self.client.delete(&url).bearer_auth(token)).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TDAmeritradeClientWatchlists, Watchlist, WatchlistItem, WatchlistRequest};
+    use crate::tda_client::{
+        accounts::AssetType,
+        error::TdaError,
+        mock::{MockTda, ACCESS_TOKEN, ACCOUNT_ID, CREATED_WATCHLIST_ID},
+    };
+    use axum::http::StatusCode;
+    use serde_json::json;
+
+    const WATCHLISTS: &str = include_str!("fixtures/watchlists.json");
+
+    #[test]
+    fn test_deserialize_watchlists() {
+        let watchlists = serde_json::from_str::<Vec<Watchlist>>(WATCHLISTS).unwrap();
+        assert_eq!(watchlists.len(), 2);
+        let item = &watchlists[0].watchlist_items[0];
+        assert_eq!(item.sequence_id, Some(1));
+        assert_eq!(item.instrument.symbol, "AAPL");
+        assert_eq!(item.instrument.asset_type, AssetType::Equity);
+    }
+
+    #[test]
+    fn test_serialize_watchlist_request() {
+        let request = WatchlistRequest {
+            name: "Earnings".to_string(),
+            watchlist_id: None,
+            watchlist_items: vec![WatchlistItem::new("MSFT", AssetType::Equity)],
+        };
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            json!({
+                "name": "Earnings",
+                "watchlistItems": [
+                    { "quantity": 0.0, "averagePrice": 0.0, "commission": 0.0, "instrument": { "symbol": "MSFT", "assetType": "EQUITY" } }
+                ]
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_watchlists() {
+        let tda = MockTda::start();
+        let client = tda.client();
+        assert_eq!(client.get_watchlists_for_all_accounts(ACCESS_TOKEN).await.unwrap().len(), 2);
+        let watchlists = client.get_watchlists(ACCESS_TOKEN, ACCOUNT_ID).await.unwrap();
+        assert_eq!(watchlists.len(), 1);
+        let watchlist = client.get_watchlist(ACCESS_TOKEN, ACCOUNT_ID, &watchlists[0].watchlist_id).await.unwrap();
+        assert_eq!(watchlist.name, "Tech");
+
+        let request = WatchlistRequest {
+            name: "Earnings".to_string(),
+            watchlist_id: None,
+            watchlist_items: vec![WatchlistItem::new("MSFT", AssetType::Equity)],
+        };
+        assert_eq!(client.create_watchlist(ACCESS_TOKEN, ACCOUNT_ID, &request).await.unwrap().as_deref(), Some(CREATED_WATCHLIST_ID));
+        client.replace_watchlist(ACCESS_TOKEN, ACCOUNT_ID, CREATED_WATCHLIST_ID, &request).await.unwrap();
+        client.update_watchlist(ACCESS_TOKEN, ACCOUNT_ID, CREATED_WATCHLIST_ID, &request).await.unwrap();
+        client.delete_watchlist(ACCESS_TOKEN, ACCOUNT_ID, CREATED_WATCHLIST_ID).await.unwrap();
+        let requests = tda.requests();
+        assert_eq!(
+            requests[requests.len() - 3..],
+            [
+                "PUT /v1/accounts/123456789/watchlists/1750891232",
+                "PATCH /v1/accounts/123456789/watchlists/1750891232",
+                "DELETE /v1/accounts/123456789/watchlists/1750891232"
+            ]
+        );
+        assert!(matches!(
+            client.get_watchlist(ACCESS_TOKEN, ACCOUNT_ID, "0").await,
+            Err(TdaError::Rejected { status: StatusCode::NOT_FOUND, .. })
+        ));
+        tda.respond_next(StatusCode::CREATED, "");
+        assert_eq!(client.create_watchlist(ACCESS_TOKEN, ACCOUNT_ID, &request).await.unwrap(), None);
+    }
+}
@@ -0,0 +1,83 @@
+use aes_gcm::{
+    aead::{Aead, AeadCore, KeyInit, OsRng},
+    Aes256Gcm, Nonce,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use std::fmt::{self, Display, Formatter};
+
+const NONCE_LEN: usize = 12;
+
+#[derive(Debug)]
+pub enum CryptoError {
+    InvalidKey,
+    InvalidCiphertext,
+}
+
+impl Display for CryptoError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            CryptoError::InvalidKey => write!(f, "encryption key must be 32 bytes encoded as base64"),
+            CryptoError::InvalidCiphertext => write!(f, "ciphertext could not be decrypted"),
+        }
+    }
+}
+
+impl std::error::Error for CryptoError {}
+
+/// AES-256-GCM cipher for secrets stored at rest. Ciphertexts are base64 encoded
+/// with the random nonce prepended, so they fit in a plain text column.
+#[derive(Clone)]
+pub struct Cipher {
+    cipher: Aes256Gcm,
+}
+
+impl Cipher {
+    pub fn new(key: &str) -> Result<Self, CryptoError> {
+        let key = STANDARD.decode(key).map_err(|_| CryptoError::InvalidKey)?;
+        let cipher = Aes256Gcm::new_from_slice(&key).map_err(|_| CryptoError::InvalidKey)?;
+        Ok(Self { cipher })
+    }
+
+    pub fn encrypt(&self, plaintext: &str) -> String {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self.cipher.encrypt(&nonce, plaintext.as_bytes()).expect("AES-GCM encryption failed");
+        let mut encoded = nonce.to_vec();
+        encoded.extend(ciphertext);
+        STANDARD.encode(encoded)
+    }
+
+    pub fn decrypt(&self, encoded: &str) -> Result<String, CryptoError> {
+        let decoded = STANDARD.decode(encoded).map_err(|_| CryptoError::InvalidCiphertext)?;
+        if decoded.len() < NONCE_LEN {
+            return Err(CryptoError::InvalidCiphertext);
+        }
+        let (nonce, ciphertext) = decoded.split_at(NONCE_LEN);
+        let plaintext = self.cipher.decrypt(Nonce::from_slice(nonce), ciphertext).map_err(|_| CryptoError::InvalidCiphertext)?;
+        String::from_utf8(plaintext).map_err(|_| CryptoError::InvalidCiphertext)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Cipher;
+
+    const KEY: &str = "MDEyMzQ1Njc4OWFiY2RlZjAxMjM0NTY3ODlhYmNkZWY=";
+
+    #[test]
+    fn test_encrypt_decrypt() {
+        let cipher = Cipher::new(KEY).unwrap();
+        let encrypted = cipher.encrypt("refresh-token");
+        assert_ne!(encrypted, "refresh-token");
+        assert_ne!(encrypted, cipher.encrypt("refresh-token"));
+        assert_eq!(cipher.decrypt(&encrypted).unwrap(), "refresh-token");
+    }
+
+    #[test]
+    fn test_rejects_invalid_key_and_ciphertext() {
+        assert!(Cipher::new("too-short").is_err());
+        let cipher = Cipher::new(KEY).unwrap();
+        let other = Cipher::new("ZmVkY2JhOTg3NjU0MzIxMGZlZGNiYTk4NzY1NDMyMTA=").unwrap();
+        assert!(other.decrypt(&cipher.encrypt("refresh-token")).is_err());
+        assert!(cipher.decrypt("not base64!").is_err());
+    }
+}
@@ -7,7 +7,7 @@ pub fn get_base_url() -> url::Url {
         Ok(url) => url,
         Err(e) => {
             error!("error: {:?}", e);
-            return url::Url::parse("http://localhost:3000").unwrap();
+            url::Url::parse("http://localhost:3000").unwrap()
         }
     }
 }
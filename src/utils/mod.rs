@@ -1,3 +1,4 @@
 pub mod cookie;
+pub mod crypto;
 pub mod get_base_url;
 pub use get_base_url::get_base_url;